@@ -1,344 +1,6587 @@
-use serde::{Deserialize, Serialize};
-use web_sys::HtmlInputElement;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::rc::Rc;
+
+use gloo::events::{EventListener, EventListenerOptions};
+use gloo::render::{request_animation_frame, AnimationFrame};
+use gloo::timers::callback::{Interval, Timeout};
+use gloo::timers::future::TimeoutFuture;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    File, HtmlAudioElement, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement,
+    ScrollIntoViewOptions, ScrollLogicalPosition,
+};
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
+use yew_router::prelude::*;
 
+use crate::components::login::{unique_username, REMEMBER_KEY};
+use crate::components::toast::{push_toast, Toast, Toasts};
+use crate::i18n::{t, Lang, LANG_KEY};
 use crate::services::event_bus::EventBus;
-use crate::{services::websocket::WebsocketService, User};
+use crate::services::gif::{self, Gif};
+use crate::services::{clipboard, download, notification};
+use crate::services::websocket::{ConnectionState, WebsocketService, DEFAULT_MAX_RETRIES};
+use crate::utils::avatar::{avatar_initial, avatar_url, contrast_text_color};
+use crate::utils::command::{action_text, parse_command, shrug, Command};
+use crate::utils::emoji::is_emoji_only;
+use crate::utils::filter::{filter_text, FILTERED_WORDS};
+use crate::utils::highlight::{contains_ignore_case, highlight};
+use crate::utils::invite;
+use crate::utils::linkify::{
+    first_link, is_image_data_url, is_image_url, url_filename, IMAGE_MIME_TYPES,
+};
+use crate::utils::markdown::{render_markdown, render_message_body};
+use crate::utils::mention::{active_mention, mentions_user};
+use crate::utils::rate_limit::RateLimiter;
+use crate::utils::reaction::{self, Reactions, QUICK_REACTIONS};
+use crate::utils::shortcode::expand_shortcodes;
+use crate::utils::shortcuts::{shortcut_for, Shortcut};
+use crate::utils::storage::{self, load_bool, save_bool};
+use crate::{Route, User};
+
+// Dulu tema disimpan sebagai boolean; hanya dibaca jika THEME_KEY belum ada
+const DARK_MODE_KEY: &str = "yewchat_dark_mode";
+const THEME_KEY: &str = "yewchat_theme";
+const MUTED_KEY: &str = "yewchat_muted";
+// Daftar user yang pesannya disembunyikan (JSON array nama)
+const MUTED_USERS_KEY: &str = "yewchat_muted_users";
+const AVATAR_COLOR_KEY: &str = "yewchat_avatar_color";
+const FONT_SIZE_KEY: &str = "yewchat_font_size";
+const DENSITY_KEY: &str = "yewchat_density";
+const WORD_FILTER_KEY: &str = "yewchat_word_filter";
+const INLINE_IMAGES_KEY: &str = "yewchat_inline_images";
+const OPTIMISTIC_SEND_KEY: &str = "yewchat_optimistic_send";
+const BATCH_INBOUND_KEY: &str = "yewchat_batch_inbound";
+const REACTION_ALERTS_KEY: &str = "yewchat_reaction_alerts";
+// Diikuti nama room atau "dm:<user>", draft disimpan per percakapan
+const DRAFT_KEY_PREFIX: &str = "yewchat_draft_";
+const DRAFT_SAVE_DEBOUNCE_MS: u32 = 500;
+// Diikuti nama room; disimpan di sessionStorage agar bertahan saat reload
+const SESSION_HISTORY_KEY_PREFIX: &str = "yewchat_history_";
+const SESSION_HISTORY_LIMIT: usize = 200;
+const SESSION_HISTORY_DEBOUNCE_MS: u32 = 1000;
+const NOTIFICATION_SOUND_URL: &str = "/ding.wav";
+// Ubah ke true untuk tetap membunyikan suara saat tab sedang aktif
+const PLAY_SOUND_WHEN_FOCUSED: bool = false;
+// Jeda minimum antar suara agar burst pesan tidak menumpuk
+const SOUND_COOLDOWN_MS: f64 = 1000.0;
+// Jeda minimum antar toast frame rusak agar server yang bermasalah tidak
+// membanjiri layar
+const MALFORMED_TOAST_COOLDOWN_MS: f64 = 10_000.0;
+// Jarak (px) dari bawah yang masih dianggap "di bawah" untuk auto-scroll
+const SCROLL_BOTTOM_THRESHOLD: i32 = 80;
+// Virtualisasi daftar pesan: hanya baris di sekitar viewport yang dirender,
+// sisanya diganti spacer dengan tinggi perkiraan
+const ESTIMATED_ROW_HEIGHT: f64 = 72.0;
+const OVERSCAN_ROWS: usize = 10;
+// Event typing dikirim paling sering sekali tiap interval ini
+const TYPING_SEND_INTERVAL_MS: f64 = 2000.0;
+// Indikator typing hilang jika tidak ada update selama ini
+const TYPING_EXPIRY_MS: f64 = 4000.0;
+const MAX_MESSAGE_LEN: usize = 500;
+// Gambar yang di-drop dikirim sebagai data URL di dalam pesan, jadi ukurannya dibatasi
+const MAX_DROP_IMAGE_BYTES: f64 = 256.0 * 1024.0;
+// Counter berubah merah mulai dari panjang ini
+const MESSAGE_LEN_WARNING: usize = MAX_MESSAGE_LEN * 9 / 10;
+// Batas kirim: paling banyak RATE_LIMIT_MAX pesan dalam RATE_LIMIT_WINDOW_MS
+const RATE_LIMIT_MAX: usize = 5;
+const RATE_LIMIT_WINDOW_MS: f64 = 5000.0;
+// Tombol Send diaktifkan lagi jika gema server tidak datang dalam waktu ini
+const SEND_TIMEOUT_MS: u32 = 10_000;
+// Pesan berurutan dari pengirim yang sama dalam jendela ini digabung
+const GROUP_WINDOW_MS: f64 = 5.0 * 60.0 * 1000.0;
+// Seberapa sering label waktu relatif diperbarui
+const CLOCK_TICK_MS: u32 = 30_000;
+// Selama ini user yang keluar masih tampil di bagian "Baru saja offline"
+const RECENTLY_OFFLINE_MS: f64 = 30.0 * 60.0 * 1000.0;
+const COPIED_FEEDBACK_MS: u32 = 1000;
+// Batas tunggu daftar user pertama sebelum skeleton diganti pesan error
+const LOAD_TIMEOUT_MS: u32 = 5000;
+const SKELETON_ROWS: usize = 4;
+// Waktu untuk klik kedua pada tombol clear sebelum konfirmasi dibatalkan
+const CLEAR_CONFIRM_MS: u32 = 3000;
+// Lama snackbar "Undo" tampil setelah chat dibersihkan
+const UNDO_CLEAR_MS: u32 = 8000;
+const HISTORY_PAGE_SIZE: u32 = 50;
+// Batas pesan per percakapan yang disimpan di memori
+const MAX_MESSAGES: usize = 1000;
+// Tanpa aktivitas selama ini, status berubah menjadi away
+const AWAY_AFTER_MS: f64 = 5.0 * 60.0 * 1000.0;
+// Jeda sebelum mendaftar ulang saat server masih memegang nama kita dari
+// koneksi lama yang belum ditutup
+const NAME_RETRY_MS: u32 = 3_000;
+// Event aktivitas (mousemove dsb.) diproses paling sering sekali per interval ini
+const ACTIVITY_DEBOUNCE_MS: f64 = 1000.0;
+// Jarak dari atas (px) yang memicu permintaan halaman riwayat berikutnya
+const HISTORY_LOAD_THRESHOLD: i32 = 100;
+
+const DEFAULT_ROOM: &str = "general";
+const ROOMS: &[&str] = &[DEFAULT_ROOM, "random", "help"];
+
+// Dinaikkan saat format frame berubah dengan cara yang tidak kompatibel
+const PROTOCOL_VERSION: u32 = 1;
 
+const MAX_MENTION_SUGGESTIONS: usize = 6;
+const REPLY_SNIPPET_CHARS: usize = 80;
+// Pesan lebih panjang dari ini dipotong dengan tombol "Show more"
+const COLLAPSE_MAX_CHARS: usize = 400;
+const COLLAPSE_MAX_LINES: usize = 8;
+// Lama pesan tujuan "jump to" disorot
+const JUMP_HIGHLIGHT_MS: u32 = 2000;
+// Ukuran menu klik kanan, untuk menjaganya tetap di dalam viewport
+const CONTEXT_MENU_WIDTH: i32 = 192;
+const CONTEXT_MENU_ITEM_HEIGHT: i32 = 32;
+// Sedikit lebih lama dari animasi animate-slide-in/animate-fade-in di index.html
+const ENTER_ANIMATION_MS: u32 = 400;
+const GIF_SEARCH_DEBOUNCE_MS: u32 = 400;
+
+const EMOJIS: &[&str] = &[
+    "😀", "😃", "😄", "😁", "😆", "😅", "😂", "🤣", "😊", "😇", "🙂", "😉", "😍", "🥰", "😘", "😋",
+    "😎", "🤩", "🥳", "😏", "😢", "😭", "😡", "😱", "🤔", "🙄", "😴", "🤯", "👍", "👎", "👏", "🙏",
+    "💪", "👋", "❤️", "💔", "🔥", "✨", "🎉", "💯",
+];
+
+#[allow(clippy::enum_variant_names)]
 pub enum Msg {
     HandleMsg(String),
     SubmitMessage,
-    ToggleDarkMode,
+    SetTheme(Theme),
     ClearChat,
+    InputChanged,
+    PruneTyping,
+    ConnectionChanged(ConnectionState),
+    ShowToast(Toast),
+    DismissToast(u32),
+    ReconnectNow,
+    DismissReconnectBanner,
+    ToggleEmojiPicker,
+    InsertEmoji(String),
+    SwitchRoom(String),
+    VisibilityChanged,
+    ToggleMute,
+    ToggleSearch,
+    SearchChanged(String),
+    // Meneruskan query pencarian lokal ke server
+    SearchAll,
+    OpenSearchResult(String),
+    CaretMoved,
+    MentionNavigate(isize),
+    CompleteMention(usize),
+    DismissMention,
+    Tick,
+    SetAvatarColor(Option<String>),
+    ImageFailed(String),
+    CopyMessage(String),
+    Copied(Option<String>),
+    CopyInviteLink,
+    SignOut,
+    RetryRegister,
+    InviteCopied(bool),
+    DeleteMessage(String),
+    ToggleReaction { id: String, emoji: String },
+    Scrolled(f64),
+    LoadTimedOut,
+    ToggleSettings,
+    ToggleSidebar,
+    ToggleMuteUser(String),
+    ToggleModerationMenu(String),
+    // Membuka dialog konfirmasi; aksi baru dikirim lewat ConfirmModeration
+    RequestModeration(ModAction, String),
+    ConfirmModeration,
+    CancelModeration,
+    SetFontSize(FontSize),
+    SetDensity(Density),
+    SetLang(Lang),
+    RequestClear,
+    CancelClear,
+    // true menampilkan snackbar undo, false menutupnya dan membuang cadangan
+    ShowUndo(bool),
+    UndoClear,
+    Shortcut(Shortcut),
+    Activity,
+    OpenDm(String),
+    JumpToBottom,
+    SetExportFormat(ExportFormat),
+    ExportChat,
+    SetImportMode(ImportMode),
+    ImportFile(File),
+    // Drag and drop gambar ke area pesan
+    DragEnter,
+    DragLeave,
+    DropFile(Option<File>),
+    DroppedImageRead(Result<String, String>),
+    Imported(Result<String, String>),
+    ToggleWordFilter,
+    ToggleInlineImages,
+    ToggleOptimisticSend,
+    ToggleBatchInbound,
+    ToggleReactionAlerts,
+    FlushInbound,
+    SlowDownExpired,
+    SendTimedOut,
+    SaveDraft,
+    // Render ulang agar penghitung karakter sesuai draft yang dipulihkan
+    DraftRestored,
+    SaveSessionHistory,
+    SetUserSort(UserSort),
+    UserFilterChanged(String),
+    ToggleGifPicker,
+    GifQueryChanged(String),
+    SearchGifs,
+    // Nomor permintaan dan hasilnya; hasil permintaan lama diabaikan
+    GifsLoaded(u32, Result<Vec<Gif>, String>),
+    SendGif(String),
+    // None membatalkan balasan yang sedang disiapkan
+    ReplyTo(Option<String>),
+    JumpToMessage(String),
+    ClearHighlight,
+    // Animasi masuk pesan-pesan ini sudah selesai
+    EnterAnimationsDone(Vec<String>),
+    TogglePin(String),
+    TogglePinnedBanner,
+    // Id pesan dan posisi kursor (clientX, clientY)
+    OpenContextMenu(String, i32, i32),
+    CloseContextMenu,
+    // Menutup menu lalu menjalankan aksinya pada pesan yang diklik kanan
+    ContextAction(ContextAction),
+    // Panah atas di input kosong memuat pesan terakhir sendiri untuk diedit
+    EditLast,
+    CancelEdit,
+    ClearInput,
+    ToggleExpanded(String),
 }
 
-#[derive(Deserialize)]
+enum GifSearch {
+    Idle,
+    Loading,
+    Loaded(Vec<Gif>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextAction {
+    Reply,
+    Copy,
+    Pin,
+    Delete,
+    React(&'static str),
+}
+
+impl ContextAction {
+    // `pinned` memilih label Pin/Unpin
+    fn label(self, pinned: bool, lang: Lang) -> &'static str {
+        let key = match self {
+            ContextAction::Reply => "menu_reply",
+            ContextAction::Copy => "menu_copy",
+            ContextAction::Pin if pinned => "menu_unpin",
+            ContextAction::Pin => "menu_pin",
+            ContextAction::Delete => "menu_delete",
+            ContextAction::React(emoji) => return emoji,
+        };
+        t(key, lang)
+    }
+}
+
+// Hasil pencarian server; query disimpan untuk mengabaikan jawaban yang basi
+enum ServerSearch {
+    Idle,
+    Loading(String),
+    Loaded(String, Vec<MessageData>),
+}
+
+#[derive(Default, Serialize, Deserialize)]
 struct MessageData {
     from: String,
     message: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    timestamp: Option<f64>,
+    // Dibuat oleh client pengirim dan diteruskan server apa adanya. Dipakai
+    // untuk mencocokkan echo server dan merujuk pesan (misal saat dihapus).
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    room: Option<String>,
+    // Penerima pesan pribadi; None untuk pesan publik di room
+    #[serde(default)]
+    to: Option<String>,
+    // Id pesan yang dibalas
+    #[serde(default)]
+    reply_to: Option<String>,
+    #[serde(default)]
+    edited: bool,
+    #[serde(default)]
+    reactions: Reactions,
+    // Hanya diisi untuk pesan milik sendiri
+    #[serde(skip)]
+    status: Option<DeliveryStatus>,
+    #[serde(skip)]
+    kind: MessageKind,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum MessageKind {
+    #[default]
+    Chat,
+    // Pesan lokal dari client sendiri, tidak dikirim ke server
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DeliveryStatus {
+    Sending,
+    Sent,
+    Failed,
+}
+
+impl DeliveryStatus {
+    // Ikon dan kunci i18n untuk label aksesibilitasnya
+    fn icon(self) -> (&'static str, &'static str) {
+        match self {
+            DeliveryStatus::Sending => ("🕓", "delivery_sending"),
+            DeliveryStatus::Sent => ("✓", "delivery_sent"),
+            DeliveryStatus::Failed => ("❗", "delivery_failed"),
+        }
+    }
+}
+
+impl MessageData {
+    /// Whether `echo` from the server is the confirmation of this optimistic
+    /// message. Falls back to matching the text when the server drops the id.
+    fn is_echoed_by(&self, echo: &MessageData) -> bool {
+        self.status == Some(DeliveryStatus::Sending)
+            && match (&self.id, &echo.id) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.message == echo.message,
+            }
+    }
+}
+
+// Server boleh mengirim timestamp sebagai Unix millis atau string ISO
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawTimestamp {
+        Millis(f64),
+        Iso(String),
+    }
+
+    let ts = match Option::<RawTimestamp>::deserialize(deserializer)? {
+        Some(RawTimestamp::Millis(ms)) => Some(ms),
+        Some(RawTimestamp::Iso(s)) => Some(js_sys::Date::parse(&s)),
+        None => None,
+    };
+    Ok(ts.filter(|ms| ms.is_finite()))
+}
+
+/// Shortens `text` to at most `max_chars` characters, ending with "…" when cut.
+fn snippet(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// Returns the byte offset where a long message should be cut when collapsed,
+/// or `None` if it fits within `max_chars` characters and `max_lines` lines.
+fn collapse_point(text: &str, max_chars: usize, max_lines: usize) -> Option<usize> {
+    let by_chars = text.char_indices().nth(max_chars).map(|(i, _)| i);
+    let by_lines = text.match_indices('\n').nth(max_lines - 1).map(|(i, _)| i);
+    match (by_chars, by_lines) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Formats a Unix millis timestamp as local "HH:MM", or an empty string if it
+/// isn't a valid time.
+fn format_time(ts: f64) -> String {
+    if !is_valid_time(ts) {
+        return String::new();
+    }
+    let date = js_sys::Date::new(&JsValue::from_f64(ts));
+    format_clock(date.get_hours(), date.get_minutes())
+}
+
+// Batas Date di JavaScript: 100 juta hari dari epoch ke dua arah
+const MAX_TIME_MS: f64 = 8.64e15;
+
+/// Whether `ts` is a Unix millis timestamp that a JavaScript `Date` accepts.
+fn is_valid_time(ts: f64) -> bool {
+    ts.is_finite() && ts.abs() <= MAX_TIME_MS
+}
+
+fn format_clock(hours: u32, minutes: u32) -> String {
+    format!("{:02}:{:02}", hours, minutes)
+}
+
+/// Formats how long ago `ts` was relative to `now` (both Unix millis), e.g.
+/// "just now", "5m ago", "3h ago" or "2d ago".
+fn relative_time(ts: f64, now: f64, lang: Lang) -> String {
+    let seconds = ((now - ts) / 1000.0).floor().max(0.0) as u64;
+    let (key, count) = match seconds {
+        0..=59 => return t("just_now", lang).to_string(),
+        60..=3599 => ("minutes_ago", seconds / 60),
+        3600..=86_399 => ("hours_ago", seconds / 3600),
+        _ => ("days_ago", seconds / 86_400),
+    };
+    t(key, lang).replace("{count}", &count.to_string())
+}
+
+// Tanggal kalender lokal; bulan dihitung dari 0 seperti di JavaScript
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LocalDay {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl LocalDay {
+    fn of(ts: f64) -> Self {
+        let date = js_sys::Date::new(&JsValue::from_f64(ts));
+        Self {
+            year: date.get_full_year() as i32,
+            month: date.get_month(),
+            day: date.get_date(),
+        }
+    }
+
+    /// Days since 1970-01-01, so that consecutive days differ by one even
+    /// across month and year boundaries.
+    fn ordinal(self) -> i64 {
+        let month = self.month as i64 + 1;
+        let year = self.year as i64 - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+fn same_local_day(a: f64, b: f64) -> bool {
+    LocalDay::of(a) == LocalDay::of(b)
+}
+
+// Kunci i18n nama bulan, diindeks dari 0 seperti LocalDay::month
+const MONTHS: [&str; 12] = [
+    "month_january",
+    "month_february",
+    "month_march",
+    "month_april",
+    "month_may",
+    "month_june",
+    "month_july",
+    "month_august",
+    "month_september",
+    "month_october",
+    "month_november",
+    "month_december",
+];
+
+/// Formats `day` with the `date_short` ("March 3") or `date_long`
+/// ("March 3, 2023") pattern of `lang`.
+fn format_day(key: &str, day: LocalDay, lang: Lang) -> String {
+    t(key, lang)
+        .replace("{month}", t(MONTHS[day.month as usize % 12], lang))
+        .replace("{day}", &day.day.to_string())
+        .replace("{year}", &day.year.to_string())
+}
+
+/// Label for the date separator above messages sent on `ts`'s local day:
+/// "Today", "Yesterday", "March 3", or "March 3, 2023" for other years.
+fn day_label(ts: f64, now: f64, lang: Lang) -> String {
+    local_day_label(LocalDay::of(ts), LocalDay::of(now), lang)
+}
+
+/// [`day_label`] for calendar days that are already known.
+fn local_day_label(day: LocalDay, today: LocalDay, lang: Lang) -> String {
+    match today.ordinal() - day.ordinal() {
+        0 => t("today", lang).to_string(),
+        1 => t("yesterday", lang).to_string(),
+        _ if day.year == today.year => format_day("date_short", day, lang),
+        _ => format_day("date_long", day, lang),
+    }
+}
+
+/// Formats a Unix millis timestamp as local "YYYY-MM-DD", or an empty string
+/// if it isn't a valid time.
+fn format_date(ts: f64) -> String {
+    if !is_valid_time(ts) {
+        return String::new();
+    }
+    let date = js_sys::Date::new(&JsValue::from_f64(ts));
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+/// Formats a Unix millis timestamp as a full local date and time, e.g.
+/// "March 3, 2023, 14:05:09", or an empty string if it isn't a valid time.
+fn format_full_datetime(ts: f64, lang: Lang) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(ts));
+    if date.get_time().is_nan() {
+        return String::new();
+    }
+    let day = LocalDay {
+        year: date.get_full_year() as i32,
+        month: date.get_month(),
+        day: date.get_date(),
+    };
+    format!(
+        "{}, {:02}:{:02}:{:02}",
+        format_day("date_long", day, lang),
+        date.get_hours(),
+        date.get_minutes(),
+        date.get_seconds()
+    )
+}
+
+/// Serializes chat messages for download. System notices are local to this
+/// client and are left out.
+fn export_messages(msgs: &[MessageData], fmt: ExportFormat) -> String {
+    let msgs = msgs.iter().filter(|m| m.kind == MessageKind::Chat);
+    match fmt {
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(&msgs.collect::<Vec<_>>()).unwrap_or_default()
+        }
+        ExportFormat::Text => msgs
+            .map(|m| {
+                let time = m
+                    .timestamp
+                    .map(|ts| format!("[{} {}] ", format_date(ts), format_time(ts)))
+                    .unwrap_or_default();
+                match action_text(&m.message) {
+                    Some(action) => format!("{}* {} {}\n", time, m.from, action),
+                    None => format!("{}{}: {}\n", time, m.from, m.message),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Parses a JSON file produced by `export_messages`.
+fn parse_export(json: &str, lang: Lang) -> Result<Vec<MessageData>, String> {
+    let invalid = |error: &str| t("import_invalid", lang).replace("{error}", error);
+    let messages: Vec<MessageData> =
+        serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+    if messages.iter().any(|m| m.from.trim().is_empty()) {
+        return Err(invalid(t("import_no_sender", lang)));
+    }
+    Ok(messages)
+}
+
+/// Drops the oldest messages beyond `MAX_MESSAGES`. Returns whether any were
+/// dropped.
+fn trim_oldest(messages: &mut Vec<MessageData>) -> bool {
+    let overflow = messages.len().saturating_sub(MAX_MESSAGES);
+    messages.drain(..overflow);
+    overflow > 0
+}
+
+/// Puts `older` (a history page, oldest first) in front of `messages`. Like
+/// `trim_oldest`, the `MAX_MESSAGES` limit drops the oldest messages, here the
+/// oldest of `older`, so a history page never pushes out newer messages that
+/// the next live message would then have to trim again. Returns whether any
+/// of `older` were dropped.
+fn prepend_history(messages: &mut Vec<MessageData>, mut older: Vec<MessageData>) -> bool {
+    let room = MAX_MESSAGES.saturating_sub(messages.len());
+    let overflow = older.len().saturating_sub(room);
+    older.drain(..overflow);
+    older.append(messages);
+    *messages = older;
+    overflow > 0
+}
+
+/// Adds `imported` to `existing`, skipping messages already present (same id,
+/// or same sender, time and text when ids are missing), and keeps the list in
+/// time order. Returns how many messages were added.
+fn merge_messages(existing: &mut Vec<MessageData>, imported: Vec<MessageData>) -> usize {
+    let before = existing.len();
+    for m in imported {
+        let duplicate = existing.iter().any(|e| match (&e.id, &m.id) {
+            (Some(a), Some(b)) => a == b,
+            _ => e.from == m.from && e.timestamp == m.timestamp && e.message == m.message,
+        });
+        if !duplicate {
+            existing.push(m);
+        }
+    }
+    // Sort stabil: pesan tanpa timestamp tetap di posisinya relatif
+    existing.sort_by(|a, b| match (a.timestamp, b.timestamp) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => Ordering::Equal,
+    });
+    existing.len() - before
+}
+
+/// Whether the user is typing in a field other than `chat_input` (search,
+/// filters, the GIF picker), which the chat input shouldn't steal focus from.
+fn other_field_focused(chat_input: &HtmlTextAreaElement) -> bool {
+    gloo::utils::document().active_element().is_some_and(|el| {
+        matches!(el.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA") && el != ***chat_input
+    })
+}
+
+/// Describes who is typing, naming at most two people before summarizing:
+/// "Alice is typing", "Alice and Bob are typing", "Alice, Bob, and Carol are
+/// typing", "Alice, Bob, and 2 others are typing". The ellipsis is animated
+/// separately. Empty when nobody is typing.
+fn typing_label(names: &[String], lang: Lang) -> String {
+    match names {
+        [] => String::new(),
+        [one] => t("typing_one", lang).replace("{name}", one),
+        [a, b] => t("typing_two", lang).replace("{a}", a).replace("{b}", b),
+        [a, b, c] => t("typing_three", lang)
+            .replace("{a}", a)
+            .replace("{b}", b)
+            .replace("{c}", c),
+        [a, b, rest @ ..] => t("typing_many", lang)
+            .replace("{a}", a)
+            .replace("{b}", b)
+            .replace("{count}", &rest.len().to_string()),
+    }
+}
+
+/// Whether the OS or browser asks for reduced motion
+/// (`prefers-reduced-motion: reduce`).
+fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok())
+        .flatten()
+        .is_some_and(|query| query.matches())
+}
+
+// Id elemen DOM tiap pesan, target scroll saat melompat ke pesan yang dibalas
+fn message_dom_id(id: &str) -> String {
+    format!("message-{}", id)
+}
+
+fn session_history_key(room: &str) -> String {
+    format!("{}{}", SESSION_HISTORY_KEY_PREFIX, room)
+}
+
+/// Messages of `room` saved in this tab before a reload, if any.
+fn load_session_history(room: &str) -> Vec<MessageData> {
+    storage::session_load_string(&session_history_key(room))
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Server ids of `messages`, the starting point for deduplicating
+/// re-delivered messages.
+fn message_ids(messages: &[MessageData]) -> HashSet<String> {
+    messages.iter().filter_map(|m| m.id.clone()).collect()
+}
+
+/// Records the id of `message` in `seen`. Returns false if it was already
+/// there, meaning the server delivered the message again (as it may after a
+/// reconnect). Messages without an id can't be told apart and always count as
+/// new.
+fn first_delivery(seen: &mut HashSet<String>, message: &MessageData) -> bool {
+    match &message.id {
+        Some(id) => seen.insert(id.clone()),
+        None => true,
+    }
+}
+
+/// Saves the newest messages of `room` (at most `SESSION_HISTORY_LIMIT`),
+/// halving the amount while the storage quota is exceeded.
+fn save_session_history(room: &str, messages: &[MessageData]) {
+    let key = session_history_key(room);
+    // Pesan sistem tidak punya pengirim dan hanya berlaku untuk sesi ini
+    let messages: Vec<&MessageData> = messages
+        .iter()
+        .filter(|m| m.kind == MessageKind::Chat)
+        .collect();
+    let mut keep = messages.len().min(SESSION_HISTORY_LIMIT);
+    while keep > 0 {
+        let json = serde_json::to_string(&messages[messages.len() - keep..]).unwrap_or_default();
+        if storage::session_save_string(&key, &json) {
+            return;
+        }
+        keep /= 2;
+    }
+    storage::session_remove(&key);
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Moves focus from the `<li>` a key was pressed in to the previous or next
+/// row for the arrow keys, or to the first or last row for Home and End.
+/// Returns whether `e` was one of those keys.
+fn move_row_focus(e: &KeyboardEvent) -> bool {
+    // current_target() adalah root delegasi event Yew, bukan baris ini
+    let row = match e
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .and_then(|el| el.closest("li").ok().flatten())
+    {
+        Some(row) => row,
+        None => return false,
+    };
+    let next = match e.key().as_str() {
+        "ArrowDown" => row.next_element_sibling(),
+        "ArrowUp" => row.previous_element_sibling(),
+        "Home" => row
+            .parent_element()
+            .and_then(|list| list.first_element_child()),
+        "End" => row
+            .parent_element()
+            .and_then(|list| list.last_element_child()),
+        _ => return false,
+    };
+    if let Some(next) = next.and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok()) {
+        let _ = next.focus();
+    }
+    true
+}
+
+fn render_role_badge(role: Role, lang: Lang) -> Html {
+    match role.badge() {
+        Some((icon, label)) => html! {
+            <span class="ml-1 text-xs select-none" title={t(label, lang)} aria-label={t(label, lang)}>{icon}</span>
+        },
+        None => html! {},
+    }
+}
+
+/// Whether `next` should start a new group (avatar + name header) rather than
+/// being folded under `prev`.
+fn starts_new_group(prev: &MessageData, next: &MessageData) -> bool {
+    if prev.from != next.from || prev.kind != next.kind {
+        return true;
+    }
+    match (prev.timestamp, next.timestamp) {
+        (Some(a), Some(b)) => b - a > GROUP_WINDOW_MS || !same_local_day(a, b),
+        _ => false,
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MsgTypes {
     Users,
     Register,
     Message,
+    Typing,
+    JoinRoom,
+    DeleteMessage,
+    React,
+    Unreact,
+    History,
+    Status,
+    PrivateMessage,
+    // Server mengambil metadata OpenGraph karena browser terhalang CORS
+    LinkPreviewRequest,
+    LinkPreview,
+    Read,
+    EditMessage,
+    // Pencarian di seluruh riwayat server, dijawab dengan SearchResults
+    Search,
+    SearchResults,
+    // Data berisi id pesan yang disematkan atau dilepas
+    Pin,
+    Unpin,
+    // Data berisi nama user yang dikeluarkan; server yang memeriksa izinnya
+    Kick,
+    Ban,
+    // Server menolak Register karena nama (data) sedang dipakai
+    NameTaken,
+    // Heartbeat, ditangani WebsocketService dan tidak sampai ke komponen
+    Ping,
+    Pong,
+    // Tipe dari versi server yang lebih baru; diabaikan, bukan membuat
+    // seluruh frame gagal di-parse
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Presence {
+    #[default]
+    Online,
+    Away,
+}
+
+// Peran ditentukan server; nilai yang tidak dikenal dianggap user biasa
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Admin,
+    #[serde(alias = "moderator")]
+    Mod,
+    #[default]
+    #[serde(other)]
+    User,
+}
+
+impl Role {
+    // Ikon dan kunci i18n label untuk badge; user biasa tanpa badge
+    fn badge(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Role::Admin => Some(("⭐", "role_admin")),
+            Role::Mod => Some(("🛡️", "role_mod")),
+            Role::User => None,
+        }
+    }
+
+    /// Whether a user with this role may kick or ban a user with role `target`.
+    /// Moderators can't act on admins or other moderators.
+    fn can_moderate(self, target: Role) -> bool {
+        match self {
+            Role::Admin => target != Role::Admin,
+            Role::Mod => target == Role::User,
+            Role::User => false,
+        }
+    }
+
+    fn name_class(self) -> Option<&'static str> {
+        match self {
+            Role::Admin => Some("text-amber-500"),
+            Role::Mod => Some("text-emerald-500"),
+            Role::User => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModAction {
+    Kick,
+    Ban,
+}
+
+impl ModAction {
+    fn msg_type(self) -> MsgTypes {
+        match self {
+            ModAction::Kick => MsgTypes::Kick,
+            ModAction::Ban => MsgTypes::Ban,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            ModAction::Kick => "kick",
+            ModAction::Ban => "ban",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StatusUpdate {
+    user: String,
+    status: Presence,
+}
+
+// Pesan terakhir yang sudah dilihat `user` di room
+#[derive(Serialize, Deserialize)]
+struct ReadReceipt {
+    user: String,
+    id: String,
+}
+
+// Balasan LinkPreview; tanpa title dan description berarti gagal diambil
+#[derive(Clone, Deserialize)]
+struct LinkPreview {
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    image: Option<String>,
+}
+
+enum PreviewState {
+    Pending,
+    Ready(LinkPreview),
+    Unavailable,
+}
+
+// Permintaan satu halaman riwayat, `before` berupa timestamp pesan tertua
+#[derive(Serialize)]
+struct HistoryRequest {
+    before: Option<f64>,
+    limit: u32,
+}
+
+#[derive(Serialize)]
+struct SearchRequest {
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    query: String,
+    messages: Vec<MessageData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryPage {
+    messages: Vec<MessageData>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+// Rujukan ke pesan tertentu; `from` ikut dikirim agar hanya pesan milik
+// pengirim yang bisa diubah
+#[derive(Serialize, Deserialize)]
+struct MessageRef {
+    id: String,
+    from: String,
+}
+
+// Isi baru pesan yang diedit; seperti MessageRef, hanya berlaku untuk pesan
+// milik `from`
+#[derive(Serialize, Deserialize)]
+struct MessageEdit {
+    id: String,
+    from: String,
+    message: String,
+}
+
+// Dipakai untuk React (tambah) maupun Unreact (hapus)
+#[derive(Serialize, Deserialize)]
+struct ReactionEvent {
+    id: String,
+    emoji: String,
+    user: String,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct WebSocketMessage {
+    // Versi protokol pengirim; server lama tidak mengirimnya (0)
+    #[serde(default)]
+    version: u32,
     message_type: MsgTypes,
     data_array: Option<Vec<String>>,
     data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    room: Option<String>,
+    // Penerima, hanya untuk PrivateMessage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reply_to: Option<String>,
+    // Profil pengirim, hanya untuk Register
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<Profile>,
+    // Profil tiap user yang online, menyertai daftar Users
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profiles: Option<HashMap<String, Profile>>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    avatar: Option<String>,
+    // Hanya dari server, tidak pernah dikirim saat register
+    #[serde(default, skip_serializing)]
+    role: Role,
+}
+
+impl WebSocketMessage {
+    fn new(message_type: MsgTypes, data: Option<String>) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            message_type,
+            data_array: None,
+            data,
+            id: None,
+            room: None,
+            to: None,
+            reply_to: None,
+            profile: None,
+            profiles: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum FrameError {
+    // Bukan JSON yang diharapkan, baik frame-nya maupun isi `data`
+    Malformed(serde_json::Error),
+    // Tipe frame ini butuh `data` tapi tidak dikirim
+    MissingData,
 }
 
-#[derive(Clone)]
+/// Parses a raw frame from the server. Frames of a type this client doesn't
+/// know parse to `None` so they can be skipped.
+fn parse_frame(s: &str) -> Result<Option<WebSocketMessage>, serde_json::Error> {
+    let msg: WebSocketMessage = serde_json::from_str(s)?;
+    Ok((msg.message_type != MsgTypes::Unknown).then_some(msg))
+}
+
+/// Parses the JSON payload that a frame carries as a string in `data`.
+fn parse_frame_data<T: DeserializeOwned>(data: Option<&str>) -> Result<T, FrameError> {
+    let data = data.ok_or(FrameError::MissingData)?;
+    serde_json::from_str(data).map_err(FrameError::Malformed)
+}
+
+#[derive(Debug, Clone, PartialEq)]
 struct UserProfile {
     name: String,
-    color: String, // Ganti avatar dengan warna
+    color: String,
+    avatar: String,
+    status: Presence,
+    role: Role,
+}
+
+// Profil milik client sendiri, dipakai saat server belum mengirim profil kita
+struct OwnProfile<'a> {
+    name: &'a str,
+    color: Option<String>,
+    avatar: Option<String>,
+}
+
+/// Builds the user list for `names`, reusing the existing profile of each
+/// user in `known` and only creating profiles for new names. Profile fields
+/// from the server take precedence, then our own local choices.
+fn merge_user_profiles(
+    known: &[UserProfile],
+    names: &[String],
+    profiles: &HashMap<String, Profile>,
+    statuses: &HashMap<String, Presence>,
+    own_profile: &OwnProfile,
+) -> Vec<UserProfile> {
+    let known: HashMap<&str, &UserProfile> = known.iter().map(|u| (u.name.as_str(), u)).collect();
+    names
+        .iter()
+        .map(|name| {
+            let mut user = known
+                .get(name.as_str())
+                .map(|u| (*u).clone())
+                .unwrap_or_else(|| UserProfile {
+                    name: name.clone(),
+                    color: Chat::get_user_color(name),
+                    avatar: name.clone(),
+                    status: Presence::Online,
+                    role: Role::User,
+                });
+            user.status = statuses.get(name).copied().unwrap_or_default();
+            let own = *name == own_profile.name;
+            let profile = profiles.get(name);
+            user.role = profile.map(|p| p.role).unwrap_or_default();
+            let color = profile
+                .and_then(|p| p.color.clone())
+                .filter(|c| is_hex_color(c))
+                .or_else(|| own_profile.color.clone().filter(|_| own));
+            if let Some(color) = color {
+                user.color = color;
+            }
+            let avatar = profile
+                .and_then(|p| p.avatar.clone())
+                .or_else(|| own_profile.avatar.clone().filter(|_| own));
+            if let Some(avatar) = avatar {
+                user.avatar = avatar;
+            }
+            user
+        })
+        .collect()
+}
+
+/// A chat message from another user, as reported to the host app through
+/// `ChatProps::on_message`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedMessage {
+    pub from: String,
+    pub text: String,
+    // None untuk pesan pribadi
+    pub room: Option<String>,
+    pub private: bool,
+}
+
+/// Lifecycle hooks for apps that embed the chat. All are optional.
+#[derive(Properties, PartialEq)]
+pub struct ChatProps {
+    /// Fired each time the connection to the server opens, including reconnects.
+    #[prop_or_default]
+    pub on_connected: Callback<()>,
+    /// Fired when an open connection drops, whether or not it will retry.
+    #[prop_or_default]
+    pub on_disconnected: Callback<()>,
+    /// Fired for every room or private message received from another user.
+    #[prop_or_default]
+    pub on_message: Callback<ReceivedMessage>,
 }
 
 pub struct Chat {
     users: Vec<UserProfile>,
+    user_sort: UserSort,
+    user_filter: String,
     chat_input: NodeRef,
+    messages_container: NodeRef,
+    scroll_to_bottom: bool,
     _producer: Box<dyn Bridge<EventBus>>,
     wss: WebsocketService,
     messages: Vec<MessageData>,
-    dark_mode: bool,
+    theme: Theme,
+    username: String,
+    typing_users: HashMap<String, f64>,
+    last_typing_sent: f64,
+    typing_ticker: Option<Interval>,
+    clock: Option<Interval>,
+    connection: ConnectionState,
+    toasts: Vec<Toast>,
+    // dragenter/dragleave juga terpicu di elemen anak, jadi dihitung kedalamannya
+    drag_depth: u32,
+    // Koneksi pernah terputus sejak terakhir tersambung
+    connection_dropped: bool,
+    // Banner "Reconnect now" ditutup user; muncul lagi pada pemutusan berikutnya
+    reconnect_banner_dismissed: bool,
+    id_counter: u32,
+    emoji_picker_open: bool,
+    gif_picker_open: bool,
+    gif_query: String,
+    gif_search: GifSearch,
+    gif_search_timeout: Option<Timeout>,
+    gif_request: u32,
+    input_len: usize,
+    over_limit_attempt: bool,
+    rate_limiter: RateLimiter,
+    // Aktif selama pengiriman ditahan karena terlalu cepat
+    slow_down: Option<Timeout>,
+    // Id pesan yang menunggu gema server; tombol Send nonaktif selama itu
+    sending: Option<String>,
+    send_timeout: Option<Timeout>,
+    draft_timeout: Option<Timeout>,
+    session_history_timeout: Option<Timeout>,
+    // Isi input diganti draft percakapan aktif pada render berikutnya
+    restore_draft: bool,
+    rooms: Vec<String>,
+    current_room: String,
+    unread_count: u32,
+    base_title: String,
+    _visibility_listener: EventListener,
+    notification_permission_requested: bool,
+    muted: bool,
+    // Sensor kata kasar di pesan yang ditampilkan
+    filter_enabled: bool,
+    // Jika false, gambar (pesan maupun preview link) hanya tampil sebagai link
+    inline_images: bool,
+    // Jika false, pesan sendiri baru tampil setelah gema dari server (untuk
+    // menguji latensi dan urutan pesan)
+    optimistic_send: bool,
+    // Jika true, pesan masuk dikumpulkan lalu diterapkan sekali per animation
+    // frame agar banjir pesan tidak memicu render untuk tiap pesan
+    batch_inbound: bool,
+    inbound_queue: Vec<WebSocketMessage>,
+    inbound_frame: Option<AnimationFrame>,
+    // User yang keluar dari room beserta waktu terakhir terlihat, terbaru dulu
+    recently_offline: Vec<(String, f64)>,
+    // Nama sudah diterima server sekali di sesi ini. Sesudahnya nama tidak
+    // diganti lagi, karena setelah reconnect server bisa saja masih
+    // mencantumkan socket lama kita
+    name_confirmed: bool,
+    name_retry: Option<Timeout>,
+    // Reaksi orang lain ke pesan kita saat tab tersembunyi ikut dihitung
+    // sebagai unread dan memunculkan notifikasi desktop
+    reaction_alerts: bool,
+    last_sound_at: f64,
+    last_malformed_toast_at: f64,
+    notification_sound: Option<HtmlAudioElement>,
+    search_open: bool,
+    search_query: String,
+    server_search: ServerSearch,
+    mention_suggestions: Vec<String>,
+    mention_selected: usize,
+    // Posisi '@' (offset UTF-16) dari mention yang sedang diketik
+    mention_start: u32,
+    mention_dismissed_at: Option<u32>,
+    // Daftar user pertama setelah register tidak diumumkan sebagai join
+    users_loaded: bool,
+    user: User,
+    // URL gambar (avatar atau pesan) yang gagal dimuat, diganti fallback
+    failed_images: HashSet<String>,
+    // Id pesan panjang yang sedang dibuka penuh
+    expanded_messages: HashSet<String>,
+    // Id pesan yang akan dibalas oleh pesan berikutnya
+    replying_to: Option<String>,
+    // Id pesan sendiri yang sedang diedit di input
+    editing: Option<String>,
+    // Pesan yang di-scroll ke tengah layar pada render berikutnya
+    jump_target: Option<String>,
+    highlighted: Option<String>,
+    highlight_timeout: Option<Timeout>,
+    // Pesan baru yang sedang beranimasi masuk. Penandanya dihapus setelah
+    // animasi agar pesan tidak beranimasi lagi saat di-render ulang dari awal
+    just_added: HashSet<String>,
+    // Id pesan yang sudah diterima di room ini. Server kadang mengirim ulang
+    // pesan terbaru setelah reconnect, dan pesan itu tidak boleh dobel
+    seen_message_ids: HashSet<String>,
+    enter_animation_timeout: Option<Timeout>,
+    reduced_motion: bool,
+    // Menu klik kanan: id pesan dan posisi kursor
+    context_menu: Option<(String, i32, i32)>,
+    // Id pesan yang disematkan, urut dari yang paling lama
+    pinned_messages: Vec<String>,
+    pinned_expanded: bool,
+    // Preview per URL, diminta sekali saja
+    link_previews: HashMap<String, PreviewState>,
+    // Id pesan terakhir yang dibaca tiap user lain di room aktif
+    read_receipts: HashMap<String, String>,
+    last_read_sent: Option<String>,
+    // Teks pesan yang baru saja disalin, untuk label "Copied!"
+    copied: Option<String>,
+    // Konfirmasi "Link copied!" setelah link undangan disalin
+    invite_copied: bool,
+    scroll_top: f64,
+    viewport_height: f64,
+    // Saat di dasar, jendela render selalu memuat pesan terakhir
+    pinned_to_bottom: bool,
+    // Pesan yang masuk sejak user scroll menjauh dari dasar
+    unseen_count: u32,
+    // Pesan terakhir yang terlihat sebelum tab ditinggalkan; pemisah "New
+    // messages" digambar setelahnya sampai user menyusul ke bawah
+    unread_marker: Option<String>,
+    load_timeout: Option<Timeout>,
+    load_timed_out: bool,
+    has_more_history: bool,
+    history_loading: bool,
+    // Tinggi scroll sebelum riwayat disisipkan, agar posisi baca tidak lompat
+    history_scroll_anchor: Option<i32>,
+    settings_open: bool,
+    // User yang pesannya disembunyikan, hanya di browser ini
+    muted_users: HashSet<String>,
+    // User yang menu moderasinya sedang terbuka di sidebar
+    moderation_menu: Option<String>,
+    confirm_moderation: Option<(ModAction, String)>,
+    // Diisi saat kita sendiri dikeluarkan; koneksi sudah ditutup
+    removed: Option<ModAction>,
+    // Sidebar sebagai drawer di layar sempit; di desktop selalu tampil
+    sidebar_open: bool,
+    font_size: FontSize,
+    lang: Lang,
+    density: Density,
+    export_format: ExportFormat,
+    import_mode: ImportMode,
+    import_error: Option<String>,
+    // Timer selama tombol clear menunggu klik konfirmasi
+    confirm_clear: Option<Timeout>,
+    // Pesan yang baru dibersihkan, bisa dikembalikan lewat snackbar
+    cleared_backup: Vec<MessageData>,
+    undo_timeout: Option<Timeout>,
+    _keydown_listener: EventListener,
+    search_input: NodeRef,
+    focus_search: bool,
+    // Kembalikan fokus ke input chat setelah render berikutnya
+    focus_input: bool,
+    _activity_listeners: Vec<EventListener>,
+    last_activity: f64,
+    // Status milik sendiri yang terakhir dikirim ke server
+    presence: Presence,
+    // Status user lain dari server; yang tidak ada dianggap online
+    statuses: HashMap<String, Presence>,
+    // Pesan pribadi per lawan bicara, terpisah dari pesan publik
+    conversations: HashMap<String, Vec<MessageData>>,
+    // Lawan bicara DM yang sedang dibuka; None berarti room publik
+    active_dm: Option<String>,
+    dm_unread: HashMap<String, u32>,
 }
 
-impl Chat {
-    // Fungsi untuk generate warna berdasarkan nama user
-    fn get_user_color(name: &str) -> String {
-        let colors = vec![
-            "#EF4444", "#F97316", "#F59E0B", "#EAB308", 
-            "#84CC16", "#22C55E", "#10B981", "#14B8A6",
-            "#06B6D4", "#0EA5E9", "#3B82F6", "#6366F1",
-            "#8B5CF6", "#A855F7", "#D946EF", "#EC4899"
-        ];
-        let index = name.chars().map(|c| c as usize).sum::<usize>() % colors.len();
-        colors[index].to_string()
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl FontSize {
+    const ALL: [FontSize; 3] = [FontSize::Small, FontSize::Medium, FontSize::Large];
+
+    // Nilai yang disimpan di localStorage
+    fn key(self) -> &'static str {
+        match self {
+            FontSize::Small => "small",
+            FontSize::Medium => "medium",
+            FontSize::Large => "large",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|size| size.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            FontSize::Small => "font_small",
+            FontSize::Medium => "font_medium",
+            FontSize::Large => "font_large",
+        };
+        t(key, lang)
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            FontSize::Small => "text-xs",
+            FontSize::Medium => "text-sm",
+            FontSize::Large => "text-base",
+        }
     }
 }
 
-impl Component for Chat {
-    type Message = Msg;
-    type Properties = ();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+    HighContrast,
+}
 
-    fn create(ctx: &Context<Self>) -> Self {
-        let (user, _) = ctx
-            .link()
-            .context::<User>(Callback::noop())
-            .expect("context to be set");
-        let wss = WebsocketService::new();
-        let username = user.username.borrow().clone();
+impl Theme {
+    const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
 
-        let message = WebSocketMessage {
-            message_type: MsgTypes::Register,
-            data: Some(username.to_string()),
-            data_array: None,
+    fn key(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            Theme::Light => "theme_light",
+            Theme::Dark => "theme_dark",
+            Theme::HighContrast => "theme_high_contrast",
         };
+        t(key, lang)
+    }
 
-        if let Ok(_) = wss
-            .tx
-            .clone()
-            .try_send(serde_json::to_string(&message).unwrap())
-        {
-            log::debug!("message sent successfully");
+    // Kelas di elemen root yang mengisi variabel warna PALETTE
+    fn class(self) -> &'static str {
+        match self {
+            Theme::Light => "theme-light",
+            Theme::Dark => "theme-dark",
+            Theme::HighContrast => "theme-high-contrast",
         }
+    }
 
-        Self {
-            users: vec![],
-            messages: vec![],
-            chat_input: NodeRef::default(),
-            wss,
-            _producer: EventBus::bridge(ctx.link().callback(Msg::HandleMsg)),
-            dark_mode: false,
+    // Urutan untuk shortcut Ctrl+D
+    fn next(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::HighContrast,
+            Theme::HighContrast => Theme::Light,
         }
     }
+}
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            Msg::HandleMsg(s) => {
-                let msg: WebSocketMessage = serde_json::from_str(&s).unwrap();
-                match msg.message_type {
-                    MsgTypes::Users => {
-                        let users_from_message = msg.data_array.unwrap_or_default();
-                        self.users = users_from_message
-                            .iter()
-                            .map(|u| UserProfile {
-                                name: u.clone(),
-                                color: Self::get_user_color(u),
-                            })
-                            .collect();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Density {
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    const ALL: [Density; 2] = [Density::Comfortable, Density::Compact];
+
+    fn key(self) -> &'static str {
+        match self {
+            Density::Comfortable => "comfortable",
+            Density::Compact => "compact",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|density| density.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            Density::Comfortable => "density_comfortable",
+            Density::Compact => "density_compact",
+        };
+        t(key, lang)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Text,
+}
+
+impl ExportFormat {
+    const ALL: [ExportFormat; 2] = [ExportFormat::Json, ExportFormat::Text];
+
+    fn key(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Text => "txt",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|fmt| fmt.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            ExportFormat::Json => "export_json",
+            ExportFormat::Text => "export_text",
+        };
+        t(key, lang)
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Text => "text/plain",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    Merge,
+    Replace,
+}
+
+impl ImportMode {
+    const ALL: [ImportMode; 2] = [ImportMode::Merge, ImportMode::Replace];
+
+    fn key(self) -> &'static str {
+        match self {
+            ImportMode::Merge => "merge",
+            ImportMode::Replace => "replace",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            ImportMode::Merge => "import_merge",
+            ImportMode::Replace => "import_replace",
+        };
+        t(key, lang)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserSort {
+    // Urutan dari server, yaitu urutan masuk
+    Arrival,
+    Name,
+    Status,
+}
+
+impl UserSort {
+    const ALL: [UserSort; 3] = [UserSort::Arrival, UserSort::Name, UserSort::Status];
+
+    fn key(self) -> &'static str {
+        match self {
+            UserSort::Arrival => "arrival",
+            UserSort::Name => "name",
+            UserSort::Status => "status",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            UserSort::Arrival => "sort_arrival",
+            UserSort::Name => "sort_name",
+            UserSort::Status => "sort_status",
+        };
+        t(key, lang)
+    }
+}
+
+// Theme classes. Warnanya berasal dari variabel CSS yang diisi oleh kelas
+// Theme::class() di elemen root, lihat static/index.html
+struct Palette {
+    bg_primary: &'static str,
+    bg_secondary: &'static str,
+    bg_tertiary: &'static str,
+    text_primary: &'static str,
+    text_secondary: &'static str,
+    border_color: &'static str,
+    hover_bg: &'static str,
+}
+
+const PALETTE: Palette = Palette {
+    bg_primary: "theme-bg-primary",
+    bg_secondary: "theme-bg-secondary",
+    bg_tertiary: "theme-bg-tertiary",
+    text_primary: "theme-text-primary",
+    text_secondary: "theme-text-secondary",
+    border_color: "theme-border",
+    hover_bg: "theme-hover",
+};
+
+// Warna dipakai langsung di atribut style, jadi hanya terima "#rrggbb"
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Menyesuaikan tinggi textarea dengan isinya (dibatasi max-h lewat CSS)
+fn fit_to_content(textarea: &HtmlTextAreaElement) {
+    let style = textarea.style();
+    let _ = style.set_property("height", "auto");
+    let _ = style.set_property("height", &format!("{}px", textarea.scroll_height()));
+}
+
+impl Chat {
+    // Fungsi untuk generate warna berdasarkan nama user
+    fn get_user_color(name: &str) -> String {
+        let colors = vec![
+            "#EF4444", "#F97316", "#F59E0B", "#EAB308", 
+            "#84CC16", "#22C55E", "#10B981", "#14B8A6",
+            "#06B6D4", "#0EA5E9", "#3B82F6", "#6366F1",
+            "#8B5CF6", "#A855F7", "#D946EF", "#EC4899"
+        ];
+        // Hash polinomial (FNV-1a) agar nama beranagram tidak selalu sewarna
+        let hash = name
+            .bytes()
+            .fold(0x811c_9dc5_u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x0100_0193));
+        let index = hash as usize % colors.len();
+        colors[index].to_string()
+    }
+
+    /// Counts a reaction to one of our messages as unread and shows a
+    /// desktop notification, if enabled and the tab is hidden.
+    fn alert_reaction(&mut self, event: &ReactionEvent, text: &str) {
+        // Reaksi sendiri dari tab lain tidak perlu diberitahukan
+        if !self.reaction_alerts
+            || event.user == self.username
+            || self.muted_users.contains(&event.user)
+            || !gloo::utils::document().hidden()
+        {
+            return;
+        }
+        self.unread_count += 1;
+        self.update_title();
+        let title = t("reaction_notification", self.lang)
+            .replace("{name}", &event.user)
+            .replace("{emoji}", &event.emoji);
+        notification::notify(&title, text);
+    }
+
+    // `force` dipakai untuk mention: tetap berbunyi walau tab sedang aktif
+    fn play_notification_sound(&mut self, force: bool) {
+        let focused = !gloo::utils::document().hidden();
+        if self.muted || (!force && !PLAY_SOUND_WHEN_FOCUSED && focused) {
+            return;
+        }
+        let now = js_sys::Date::now();
+        if now - self.last_sound_at < SOUND_COOLDOWN_MS {
+            return;
+        }
+        self.last_sound_at = now;
+
+        // Satu elemen audio dipakai ulang sehingga suara tidak saling tumpuk
+        if self.notification_sound.is_none() {
+            self.notification_sound = HtmlAudioElement::new_with_src(NOTIFICATION_SOUND_URL).ok();
+        }
+        if let Some(audio) = &self.notification_sound {
+            audio.set_current_time(0.0);
+            match audio.play() {
+                Ok(promise) => spawn_local(async move {
+                    // Ditolak jika browser memblokir autoplay
+                    if let Err(e) = JsFuture::from(promise).await {
+                        log::debug!("error playing notification sound: {:?}", e);
+                    }
+                }),
+                Err(e) => log::debug!("error playing notification sound: {:?}", e),
+            }
+        }
+    }
+
+    fn is_near_bottom(&self) -> bool {
+        match self.messages_container.cast::<web_sys::Element>() {
+            Some(el) => {
+                el.scroll_height() - el.scroll_top() - el.client_height() <= SCROLL_BOTTOM_THRESHOLD
+            }
+            None => true,
+        }
+    }
+
+    fn load_timeout(ctx: &Context<Self>) -> Timeout {
+        let link = ctx.link().clone();
+        Timeout::new(LOAD_TIMEOUT_MS, move || {
+            link.send_message(Msg::LoadTimedOut)
+        })
+    }
+
+    // Masih menunggu daftar user pertama dan belum melewati batas waktu
+    fn is_loading(&self) -> bool {
+        !self.users_loaded && !self.load_timed_out
+    }
+
+    /// Messages of the open view: the active DM conversation, or the room.
+    fn current_messages(&self) -> &[MessageData] {
+        match &self.active_dm {
+            Some(peer) => self.conversations.get(peer).map_or(&[], Vec::as_slice),
+            None => &self.messages,
+        }
+    }
+
+    fn current_messages_mut(&mut self) -> &mut Vec<MessageData> {
+        match &self.active_dm {
+            Some(peer) => self.conversations.entry(peer.clone()).or_default(),
+            None => &mut self.messages,
+        }
+    }
+
+    /// Files a private message under the conversation with the other user.
+    /// Returns whether the open view changed.
+    fn receive_private(&mut self, mut message_data: MessageData) -> bool {
+        let peer = if message_data.from == self.username {
+            match message_data.to.clone() {
+                Some(to) => to,
+                None => return false,
+            }
+        } else {
+            message_data.from.clone()
+        };
+        let viewing = self.active_dm.as_ref() == Some(&peer);
+        let echo = message_data.from == self.username
+            && self
+                .conversations
+                .get(&peer)
+                .is_some_and(|c| c.iter().any(|m| m.is_echoed_by(&message_data)));
+        if !echo {
+            self.mark_just_added(&message_data);
+        }
+        let conversation = self.conversations.entry(peer.clone()).or_default();
+        if message_data.from == self.username {
+            if let Some(pending) = conversation
+                .iter_mut()
+                .find(|m| m.is_echoed_by(&message_data))
+            {
+                pending.status = Some(DeliveryStatus::Sent);
+                pending.timestamp = message_data.timestamp;
+                return viewing;
+            }
+            message_data.status = Some(DeliveryStatus::Sent);
+            conversation.push(message_data);
+            trim_oldest(conversation);
+            self.scroll_to_bottom = true;
+            return viewing;
+        } else if self.muted_users.contains(&message_data.from) {
+            // Tetap disimpan (tampil sebagai placeholder) tanpa notifikasi
+            conversation.push(message_data);
+            trim_oldest(conversation);
+            return viewing;
+        } else {
+            let hidden = gloo::utils::document().hidden();
+            if hidden || !viewing {
+                notification::notify(&format!("🔒 {}", message_data.from), &message_data.message);
+            }
+            conversation.push(message_data);
+            trim_oldest(conversation);
+            if !viewing {
+                *self.dm_unread.entry(peer).or_default() += 1;
+                self.play_notification_sound(true);
+                // Badge di sidebar ikut berubah
+                return true;
+            }
+            if hidden {
+                self.unread_count += 1;
+                self.update_title();
+            }
+            self.play_notification_sound(false);
+        }
+        self.scroll_to_bottom = self.is_near_bottom();
+        if !self.scroll_to_bottom {
+            self.unseen_count += 1;
+        }
+        true
+    }
+
+    /// Users shown in the sidebar: those matching the name filter, in the
+    /// chosen order. Sorting is stable, so ties keep their arrival order.
+    fn displayed_users(&self) -> Vec<&UserProfile> {
+        let filter = self.user_filter.trim();
+        let mut users: Vec<&UserProfile> = self
+            .users
+            .iter()
+            .filter(|u| filter.is_empty() || contains_ignore_case(&u.name, filter))
+            .collect();
+        match self.user_sort {
+            UserSort::Arrival => {}
+            UserSort::Name => users.sort_by_key(|u| u.name.to_lowercase()),
+            UserSort::Status => users.sort_by_key(|u| u.status == Presence::Away),
+        }
+        users
+    }
+
+    fn visible_messages(&self) -> Vec<&MessageData> {
+        self.current_messages()
+            .iter()
+            .filter(|m| self.matches_search(m))
+            .collect()
+    }
+
+    /// Index range of the `count` visible messages that should be rendered.
+    fn message_window(&self, count: usize) -> (usize, usize) {
+        let rows =
+            (self.viewport_height / ESTIMATED_ROW_HEIGHT).ceil() as usize + 2 * OVERSCAN_ROWS;
+        if self.pinned_to_bottom || self.scroll_to_bottom {
+            return (count.saturating_sub(rows), count);
+        }
+        let start = ((self.scroll_top / ESTIMATED_ROW_HEIGHT) as usize)
+            .saturating_sub(OVERSCAN_ROWS)
+            .min(count);
+        (start, (start + rows).min(count))
+    }
+
+    fn show_toast(&mut self, toast: Toast) {
+        push_toast(&mut self.toasts, toast);
+    }
+
+    /// Warns about a frame that couldn't be read, at most once per cooldown.
+    /// Returns whether a toast was shown and the view needs a render.
+    fn warn_malformed_frame(&mut self) -> bool {
+        let now = js_sys::Date::now();
+        if now - self.last_malformed_toast_at < MALFORMED_TOAST_COOLDOWN_MS {
+            return false;
+        }
+        self.last_malformed_toast_at = now;
+        self.show_toast(Toast::warning(t("toast_malformed_frame", self.lang)));
+        true
+    }
+
+    /// Reads the `data` payload of a `kind` frame. On failure the error is
+    /// logged and `Err` carries whether the view needs a render.
+    fn frame_data<T: DeserializeOwned>(
+        &mut self,
+        kind: &str,
+        data: Option<&str>,
+    ) -> Result<T, bool> {
+        match parse_frame_data(data) {
+            Ok(value) => Ok(value),
+            // Bisa saja disengaja server, cukup dicatat
+            Err(FrameError::MissingData) => {
+                log::warn!("ignoring {} frame without data", kind);
+                Err(false)
+            }
+            Err(FrameError::Malformed(e)) => {
+                log::warn!("ignoring malformed {} {:?}: {}", kind, data, e);
+                Err(self.warn_malformed_frame())
+            }
+        }
+    }
+
+    /// Marks a message about to be added so it animates in, unless the user
+    /// prefers reduced motion.
+    fn mark_just_added(&mut self, message: &MessageData) {
+        if self.reduced_motion {
+            return;
+        }
+        if let Some(id) = &message.id {
+            self.just_added.insert(id.clone());
+        }
+    }
+
+    fn send(&self, message: &WebSocketMessage) -> bool {
+        match self.wss.tx.clone().try_send(
+            serde_json::to_string(message).expect("WebSocketMessage is always serializable"),
+        ) {
+            Ok(_) => true,
+            Err(e) => {
+                log::debug!("error sending to channel: {:?}", e);
+                false
+            }
+        }
+    }
+
+    // Panjang dihitung dalam char (bukan byte) dari teks yang akan dikirim
+    fn sync_input_len(&mut self) {
+        if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+            self.input_len = input.value().trim().chars().count();
+        }
+    }
+
+    /// Recomputes the @-autocomplete suggestions from the text before the caret.
+    fn sync_mention_suggestions(&mut self) {
+        let previous = std::mem::take(&mut self.mention_suggestions);
+        let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+            Some(input) => input,
+            None => return,
+        };
+        let value = input.value();
+        let caret = input.selection_start().ok().flatten().unwrap_or(0) as usize;
+        // Caret dalam UTF-16, ubah ke offset byte
+        let caret_byte = value
+            .char_indices()
+            .scan(0, |utf16, (i, c)| {
+                let at = (*utf16, i);
+                *utf16 += c.len_utf16();
+                Some(at)
+            })
+            .find(|&(utf16, _)| utf16 >= caret)
+            .map_or(value.len(), |(_, i)| i);
+
+        if let Some((at, prefix)) = active_mention(&value[..caret_byte]) {
+            let start = value[..at].encode_utf16().count() as u32;
+            if self.mention_dismissed_at == Some(start) {
+                return;
+            }
+            let prefix = prefix.to_lowercase();
+            self.mention_suggestions = self
+                .users
+                .iter()
+                .map(|u| &u.name)
+                .filter(|name| **name != self.username && name.to_lowercase().starts_with(&prefix))
+                .take(MAX_MENTION_SUGGESTIONS)
+                .cloned()
+                .collect();
+            if self.mention_start != start || self.mention_suggestions != previous {
+                self.mention_selected = 0;
+            }
+            self.mention_start = start;
+        }
+    }
+
+    fn active_search_query(&self) -> &str {
+        if self.search_open {
+            self.search_query.trim()
+        } else {
+            ""
+        }
+    }
+
+    fn matches_search(&self, m: &MessageData) -> bool {
+        let query = self.active_search_query();
+        contains_ignore_case(&m.message, query) || contains_ignore_case(&m.from, query)
+    }
+
+    fn update_title(&self) {
+        let title = if self.unread_count > 0 {
+            format!("({}) {}", self.unread_count, self.base_title)
+        } else {
+            self.base_title.clone()
+        };
+        gloo::utils::document().set_title(&title);
+    }
+
+    /// Applies one frame received from the server. Returns whether the view
+    /// needs to re-render.
+    fn handle_frame(&mut self, ctx: &Context<Self>, msg: WebSocketMessage) -> bool {
+        match msg.message_type {
+            MsgTypes::Users => {
+                let users_from_message = msg.data_array.unwrap_or_default();
+                let profiles = msg.profiles.unwrap_or_default();
+                let was_loading = !self.users_loaded;
+                // Untuk server yang tidak mengirim NameTaken: nama kita muncul
+                // dua kali berarti sudah dipakai orang lain
+                if !self.name_confirmed
+                    && users_from_message
+                        .iter()
+                        .filter(|name| **name == self.username)
+                        .count()
+                        > 1
+                {
+                    self.rename_taken_username(&users_from_message);
+                }
+                self.name_confirmed = true;
+                if self.users_loaded {
+                    self.announce_presence_changes(&users_from_message);
+                    self.track_departures(&users_from_message);
+                }
+                self.users_loaded = true;
+                self.load_timeout = None;
+                self.load_timed_out = false;
+                let users = self.merge_users(&users_from_message, &profiles);
+                // Daftar yang sama tidak perlu merender ulang sidebar
+                let changed = users != self.users;
+                self.users = users;
+                changed || was_loading
+            }
+            MsgTypes::Message | MsgTypes::PrivateMessage => {
+                let mut message_data =
+                    match self.frame_data::<MessageData>("message", msg.data.as_deref()) {
+                        Ok(message_data) => message_data,
+                        Err(render) => return render,
+                    };
+                if message_data
+                    .room
+                    .as_ref()
+                    .is_some_and(|room| *room != self.current_room)
+                {
+                    return false;
+                }
+                if !first_delivery(&mut self.seen_message_ids, &message_data) {
+                    log::debug!("ignoring re-delivered message {:?}", message_data.id);
+                    return false;
+                }
+                if message_data.timestamp.is_none() {
+                    message_data.timestamp = Some(js_sys::Date::now());
+                }
+                self.typing_users.remove(&message_data.from);
+                self.request_link_preview(&message_data.message);
+                if self.echoes_sending(&message_data) {
+                    self.sending = None;
+                    self.send_timeout = None;
+                }
+                let private =
+                    msg.message_type == MsgTypes::PrivateMessage || message_data.to.is_some();
+                if message_data.from != self.username {
+                    ctx.props().on_message.emit(ReceivedMessage {
+                        from: message_data.from.clone(),
+                        text: message_data.message.clone(),
+                        room: (!private).then(|| self.current_room.clone()),
+                        private,
+                    });
+                }
+                // Pesan pribadi tidak boleh masuk ke daftar publik
+                if private {
+                    return self.receive_private(message_data);
+                }
+                if message_data.from == self.username {
+                    // Echo server menggantikan pesan optimistis, bukan duplikat
+                    if let Some(pending) = self
+                        .messages
+                        .iter_mut()
+                        .find(|m| m.is_echoed_by(&message_data))
+                    {
+                        pending.status = Some(DeliveryStatus::Sent);
+                        pending.timestamp = message_data.timestamp;
+                        return true;
+                    }
+                    message_data.status = Some(DeliveryStatus::Sent);
+                } else if !self.muted_users.contains(&message_data.from) {
+                    let hidden = gloo::utils::document().hidden();
+                    let mentioned = mentions_user(&message_data.message, &self.username);
+                    if hidden {
+                        self.unread_count += 1;
+                        self.update_title();
+                    }
+                    if hidden || mentioned {
+                        notification::notify(&message_data.from, &message_data.message);
+                    }
+                    self.play_notification_sound(mentioned);
+                }
+                if self.active_dm.is_none() {
+                    self.scroll_to_bottom =
+                        message_data.from == self.username || self.is_near_bottom();
+                    if !self.scroll_to_bottom {
+                        self.unseen_count += 1;
+                    }
+                }
+                self.discard_undo();
+                self.mark_just_added(&message_data);
+                self.messages.push(message_data);
+                if trim_oldest(&mut self.messages) {
+                    self.has_more_history = true;
+                }
+                self.mark_read();
+                true
+            }
+            MsgTypes::Typing => match msg.data {
+                Some(name) if name != self.username => {
+                    self.typing_users.insert(name, js_sys::Date::now());
+                    if self.typing_ticker.is_none() {
+                        let link = ctx.link().clone();
+                        self.typing_ticker = Some(Interval::new(1000, move || {
+                            link.send_message(Msg::PruneTyping)
+                        }));
+                    }
+                    true
+                }
+                _ => false,
+            },
+            MsgTypes::DeleteMessage => {
+                let target = match self.frame_data::<MessageRef>("delete", msg.data.as_deref()) {
+                    Ok(target) => target,
+                    Err(render) => return render,
+                };
+                let mut removed = false;
+                for list in
+                    std::iter::once(&mut self.messages).chain(self.conversations.values_mut())
+                {
+                    let before = list.len();
+                    list.retain(|m| m.id.as_ref() != Some(&target.id) || m.from != target.from);
+                    removed |= list.len() != before;
+                }
+                removed
+            }
+            MsgTypes::Status => {
+                let update = match self.frame_data::<StatusUpdate>("status", msg.data.as_deref()) {
+                    Ok(update) => update,
+                    Err(render) => return render,
+                };
+                self.statuses.insert(update.user.clone(), update.status);
+                match self.users.iter_mut().find(|u| u.name == update.user) {
+                    Some(user) if user.status != update.status => {
+                        user.status = update.status;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            MsgTypes::History => {
+                self.history_loading = false;
+                let page = match self.frame_data::<HistoryPage>("history", msg.data.as_deref()) {
+                    Ok(page) => page,
+                    Err(render) => return render,
+                };
+                self.has_more_history = page.has_more;
+                let mut older: Vec<MessageData> = page
+                    .messages
+                    .into_iter()
+                    .filter(|m| m.to.is_none())
+                    .filter(|m| {
+                        m.room
+                            .as_ref()
+                            .is_none_or(|room| *room == self.current_room)
+                    })
+                    .filter(|m| {
+                        m.id.is_none() || !self.messages.iter().any(|existing| existing.id == m.id)
+                    })
+                    .collect();
+                older.sort_by(|a, b| {
+                    a.timestamp
+                        .partial_cmp(&b.timestamp)
+                        .unwrap_or(Ordering::Equal)
+                });
+                if older.is_empty() {
+                    return true;
+                }
+                for m in &older {
+                    self.request_link_preview(&m.message);
+                }
+                self.seen_message_ids.extend(message_ids(&older));
+                if self.active_dm.is_some() {
+                    // Room tidak sedang tampil, tidak ada posisi scroll untuk dijaga
+                } else if self.messages.is_empty() {
+                    self.scroll_to_bottom = true;
+                } else if let Some(el) = self.messages_container.cast::<web_sys::Element>() {
+                    self.history_scroll_anchor = Some(el.scroll_height());
+                }
+                prepend_history(&mut self.messages, older);
+                self.mark_read();
+                true
+            }
+            MsgTypes::EditMessage => {
+                let edit = match self.frame_data::<MessageEdit>("edit", msg.data.as_deref()) {
+                    Ok(edit) => edit,
+                    Err(render) => return render,
+                };
+                let mut changed = false;
+                for m in std::iter::once(&mut self.messages)
+                    .chain(self.conversations.values_mut())
+                    .flatten()
+                    .filter(|m| m.id.as_ref() == Some(&edit.id) && m.from == edit.from)
+                {
+                    m.message = edit.message.clone();
+                    m.edited = true;
+                    changed = true;
+                }
+                changed
+            }
+            MsgTypes::SearchResults => {
+                let results =
+                    match self.frame_data::<SearchResults>("search results", msg.data.as_deref()) {
+                        Ok(results) => results,
+                        Err(render) => return render,
+                    };
+                match &self.server_search {
+                    ServerSearch::Loading(query) if *query == results.query => {
+                        self.server_search = ServerSearch::Loaded(results.query, results.messages);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            MsgTypes::Pin => match msg.data {
+                Some(id) if !self.pinned_messages.contains(&id) => {
+                    self.pinned_messages.push(id);
+                    true
+                }
+                _ => false,
+            },
+            MsgTypes::Unpin => match msg.data {
+                Some(id) => {
+                    let before = self.pinned_messages.len();
+                    self.pinned_messages.retain(|pinned| *pinned != id);
+                    self.pinned_messages.len() != before
+                }
+                None => false,
+            },
+            MsgTypes::Kick | MsgTypes::Ban => {
+                let action = if msg.message_type == MsgTypes::Kick {
+                    ModAction::Kick
+                } else {
+                    ModAction::Ban
+                };
+                let target = match msg.data {
+                    Some(target) => target,
+                    None => return false,
+                };
+                if target == self.username {
+                    self.removed = Some(action);
+                    // Kalau tetap diingat, halaman login langsung kembali ke chat
+                    storage::remove(REMEMBER_KEY);
+                    // Menutup channel membuat WebsocketService berhenti dan menutup socket
+                    self.wss.tx.close_channel();
+                } else {
+                    let notice = t(&format!("{}_notice", action.key()), self.lang);
+                    self.push_system_message(notice.replace("{name}", &target));
+                }
+                true
+            }
+            MsgTypes::Read => {
+                if msg
+                    .room
+                    .as_ref()
+                    .is_some_and(|room| *room != self.current_room)
+                {
+                    return false;
+                }
+                let receipt =
+                    match self.frame_data::<ReadReceipt>("read receipt", msg.data.as_deref()) {
+                        Ok(receipt) => receipt,
+                        Err(render) => return render,
+                    };
+                if receipt.user == self.username {
+                    return false;
+                }
+                let changed = self.read_receipts.get(&receipt.user) != Some(&receipt.id);
+                self.read_receipts.insert(receipt.user, receipt.id);
+                changed
+            }
+            MsgTypes::LinkPreview => {
+                let preview =
+                    match self.frame_data::<LinkPreview>("link preview", msg.data.as_deref()) {
+                        Ok(preview) => preview,
+                        Err(render) => return render,
+                    };
+                let state = if preview.title.is_some() || preview.description.is_some() {
+                    PreviewState::Ready(preview.clone())
+                } else {
+                    PreviewState::Unavailable
+                };
+                self.link_previews.insert(preview.url, state);
+                true
+            }
+            MsgTypes::NameTaken => {
+                if msg.data.as_ref() != Some(&self.username) {
+                    return false;
+                }
+                if self.name_confirmed {
+                    // Koneksi lama kita belum dilepas server, coba lagi nanti
+                    let link = ctx.link().clone();
+                    self.name_retry = Some(Timeout::new(NAME_RETRY_MS, move || {
+                        link.send_message(Msg::RetryRegister)
+                    }));
+                    return false;
+                }
+                let taken: Vec<String> = self.users.iter().map(|u| u.name.clone()).collect();
+                self.rename_taken_username(&taken);
+                true
+            }
+            MsgTypes::React | MsgTypes::Unreact => {
+                let event = match self.frame_data::<ReactionEvent>("reaction", msg.data.as_deref())
+                {
+                    Ok(event) => event,
+                    Err(render) => return render,
+                };
+                let target = self
+                    .messages
+                    .iter_mut()
+                    .chain(self.conversations.values_mut().flatten())
+                    .find(|m| m.id.as_ref() == Some(&event.id));
+                match target {
+                    Some(m) if msg.message_type == MsgTypes::React => {
+                        let added =
+                            reaction::add_reaction(&mut m.reactions, &event.emoji, &event.user);
+                        let own = m.from == self.username;
+                        let text = m.message.clone();
+                        if added && own {
+                            self.alert_reaction(&event, &text);
+                        }
+                        added
+                    }
+                    Some(m) => {
+                        reaction::remove_reaction(&mut m.reactions, &event.emoji, &event.user)
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `echo` is the server's copy of the message being sent.
+    fn echoes_sending(&self, echo: &MessageData) -> bool {
+        let id = match &self.sending {
+            Some(id) if echo.from == self.username => id,
+            _ => return false,
+        };
+        let pending = self
+            .messages
+            .iter()
+            .chain(self.conversations.values().flatten())
+            .find(|m| m.id.as_ref() == Some(id));
+        match pending {
+            // Dicocokkan sama seperti echo pesan optimistis, termasuk server
+            // yang membuang id
+            Some(pending) => pending.is_echoed_by(echo),
+            // Instant send mati, jadi belum ada pesan lokal. Hanya satu pesan
+            // yang bisa sedang dikirim, jadi echo tanpa id pasti miliknya
+            None => echo.id.is_none() || echo.id.as_ref() == Some(id),
+        }
+    }
+
+    fn register(&self) {
+        self.send(&WebSocketMessage {
+            room: Some(self.current_room.clone()),
+            profile: Some(Profile {
+                color: self.user.color.borrow().clone(),
+                avatar: self.user.avatar.borrow().clone(),
+                ..Default::default()
+            }),
+            ..WebSocketMessage::new(MsgTypes::Register, Some(self.username.clone()))
+        });
+    }
+
+    /// Registers again under a suffixed name after the server reported
+    /// someone else online with ours. The suffixed name only lasts for this
+    /// session; the saved name stays the one the user picked.
+    fn rename_taken_username(&mut self, taken: &[String]) {
+        let mut taken = taken.to_vec();
+        taken.push(self.username.clone());
+        let name = unique_username(&self.username, &taken);
+        self.show_toast(Toast::warning(
+            t("toast_name_taken", self.lang)
+                .replace("{name}", &self.username)
+                .replace("{new}", &name),
+        ));
+        *self.user.username.borrow_mut() = name.clone();
+        self.username = name;
+        self.register();
+    }
+
+    /// Closes the topmost open popover for `Esc`; with nothing open, clears
+    /// the message box instead. Returns whether anything changed.
+    fn close_top_layer(&mut self) -> bool {
+        if self.context_menu.is_some() {
+            self.context_menu = None;
+        } else if self.emoji_picker_open {
+            self.emoji_picker_open = false;
+        } else if self.gif_picker_open {
+            self.gif_picker_open = false;
+        } else if self.settings_open {
+            self.settings_open = false;
+        } else if self.confirm_moderation.is_some() {
+            self.confirm_moderation = None;
+        } else if self.moderation_menu.is_some() {
+            self.moderation_menu = None;
+        } else if self.sidebar_open {
+            self.sidebar_open = false;
+        } else if !self.mention_suggestions.is_empty() {
+            self.mention_dismissed_at = Some(self.mention_start);
+            self.mention_suggestions.clear();
+        } else if self.confirm_clear.is_some() {
+            self.confirm_clear = None;
+        } else if self.search_open {
+            self.search_open = false;
+            self.search_query.clear();
+            self.server_search = ServerSearch::Idle;
+            self.scroll_to_bottom = true;
+        } else if self.replying_to.is_some() {
+            self.replying_to = None;
+        } else if self.editing.is_some() {
+            if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                self.reset_input(&input);
+            }
+        } else {
+            match self.chat_input.cast::<HtmlTextAreaElement>() {
+                Some(input) if !input.value().is_empty() => self.reset_input(&input),
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    // Cadangan clear tidak berlaku lagi begitu ada pesan baru
+    fn discard_undo(&mut self) -> bool {
+        self.undo_timeout = None;
+        let had_backup = !self.cleared_backup.is_empty();
+        self.cleared_backup.clear();
+        had_backup
+    }
+
+    fn set_presence(&mut self, presence: Presence) {
+        self.presence = presence;
+        self.send_presence();
+    }
+
+    fn send_presence(&self) {
+        let update = StatusUpdate {
+            user: self.username.clone(),
+            status: self.presence,
+        };
+        self.send(&WebSocketMessage::new(
+            MsgTypes::Status,
+            serde_json::to_string(&update).ok(),
+        ));
+    }
+
+    fn request_history(&mut self) {
+        // Room yang sudah penuh tidak punya tempat untuk riwayat yang lebih lama
+        if self.history_loading || !self.has_more_history || self.messages.len() >= MAX_MESSAGES {
+            return;
+        }
+        let request = HistoryRequest {
+            before: self.messages.iter().find_map(|m| m.timestamp),
+            limit: HISTORY_PAGE_SIZE,
+        };
+        self.history_loading = self.send(&WebSocketMessage {
+            room: Some(self.current_room.clone()),
+            ..WebSocketMessage::new(MsgTypes::History, serde_json::to_string(&request).ok())
+        });
+    }
+
+    /// Sends `text` to the open conversation and shows it optimistically.
+    /// Returns false without sending when the rate limit is hit.
+    fn send_chat_message(&mut self, ctx: &Context<Self>, text: String) -> bool {
+        if let Err(wait_ms) = self.rate_limiter.try_acquire(js_sys::Date::now()) {
+            let link = ctx.link().clone();
+            self.slow_down = Some(Timeout::new(wait_ms.ceil() as u32, move || {
+                link.send_message(Msg::SlowDownExpired)
+            }));
+            return false;
+        }
+
+        // Izin notifikasi hanya bisa diminta dari interaksi user
+        if !self.notification_permission_requested {
+            self.notification_permission_requested = true;
+            notification::request_permission();
+        }
+        let id = self.next_message_id();
+        let to = self.active_dm.clone();
+        self.unread_marker = None;
+        let reply_to = self.replying_to.take();
+        let message_type = if to.is_some() {
+            MsgTypes::PrivateMessage
+        } else {
+            MsgTypes::Message
+        };
+        let sent = self.send(&WebSocketMessage {
+            id: Some(id.clone()),
+            to: to.clone(),
+            reply_to: reply_to.clone(),
+            ..WebSocketMessage::new(message_type, Some(text.clone()))
+        });
+        self.discard_undo();
+        self.request_link_preview(&text);
+        if sent {
+            self.sending = Some(id.clone());
+            let link = ctx.link().clone();
+            self.send_timeout = Some(Timeout::new(SEND_TIMEOUT_MS, move || {
+                link.send_message(Msg::SendTimedOut)
+            }));
+        }
+        // Tanpa pengiriman optimistis, pesan ditambahkan saat gema server tiba.
+        // Pesan yang gagal terkirim tetap ditampilkan agar bisa dikirim ulang
+        if sent && !self.optimistic_send {
+            return true;
+        }
+        let message = MessageData {
+            from: self.username.clone(),
+            message: text,
+            timestamp: Some(js_sys::Date::now()),
+            id: Some(id),
+            room: to.is_none().then(|| self.current_room.clone()),
+            to,
+            reply_to,
+            status: Some(if sent {
+                DeliveryStatus::Sending
+            } else {
+                DeliveryStatus::Failed
+            }),
+            ..Default::default()
+        };
+        if !sent {
+            self.show_toast(Toast::error(t("toast_send_failed", self.lang)));
+        }
+        self.mark_just_added(&message);
+        self.push_message(message);
+        self.scroll_to_bottom = true;
+        true
+    }
+
+    /// Tells the server the latest room message has been seen, but only while
+    /// the room is open at the bottom of a focused, visible tab.
+    fn mark_read(&mut self) {
+        if self.active_dm.is_some() || !self.pinned_to_bottom {
+            return;
+        }
+        let document = gloo::utils::document();
+        if document.hidden() || !document.has_focus().unwrap_or(false) {
+            return;
+        }
+        let latest = self
+            .messages
+            .iter()
+            .rev()
+            .filter(|m| m.status != Some(DeliveryStatus::Failed))
+            .find_map(|m| m.id.clone());
+        let id = match latest {
+            Some(id) if self.last_read_sent.as_ref() != Some(&id) => id,
+            _ => return,
+        };
+        let receipt = ReadReceipt {
+            user: self.username.clone(),
+            id: id.clone(),
+        };
+        let sent = self.send(&WebSocketMessage {
+            room: Some(self.current_room.clone()),
+            ..WebSocketMessage::new(MsgTypes::Read, serde_json::to_string(&receipt).ok())
+        });
+        if sent {
+            self.last_read_sent = Some(id);
+        }
+    }
+
+    /// Asks the server for a preview of the first link in `message`, unless
+    /// one was already requested.
+    fn request_link_preview(&mut self, message: &str) {
+        let url = match first_link(message) {
+            Some(url) if !self.link_previews.contains_key(url) => url.to_string(),
+            _ => return,
+        };
+        let sent = self.send(&WebSocketMessage::new(
+            MsgTypes::LinkPreviewRequest,
+            Some(url.clone()),
+        ));
+        // Jika gagal terkirim, coba lagi saat link itu muncul berikutnya
+        if sent {
+            self.link_previews.insert(url, PreviewState::Pending);
+        }
+    }
+
+    fn reset_input(&mut self, input: &HtmlTextAreaElement) {
+        input.set_value("");
+        let _ = input.style().remove_property("height");
+        self.mention_suggestions.clear();
+        self.mention_dismissed_at = None;
+        self.last_typing_sent = 0.0;
+        self.input_len = 0;
+        self.draft_timeout = None;
+        self.editing = None;
+        storage::remove(&self.draft_key());
+    }
+
+    fn draft_key(&self) -> String {
+        match &self.active_dm {
+            Some(peer) => format!("{}dm:{}", DRAFT_KEY_PREFIX, peer),
+            None => format!("{}{}", DRAFT_KEY_PREFIX, self.current_room),
+        }
+    }
+
+    fn schedule_draft_save(&mut self, ctx: &Context<Self>) {
+        let link = ctx.link().clone();
+        self.draft_timeout = Some(Timeout::new(DRAFT_SAVE_DEBOUNCE_MS, move || {
+            link.send_message(Msg::SaveDraft)
+        }));
+    }
+
+    /// Stores the message box contents as the draft of the open conversation.
+    fn save_draft(&mut self) {
+        self.draft_timeout = None;
+        // Teks edit bukan draf pesan baru
+        if self.editing.is_some() {
+            return;
+        }
+        if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+            match input.value() {
+                draft if draft.trim().is_empty() => storage::remove(&self.draft_key()),
+                draft => storage::save_string(&self.draft_key(), &draft),
+            }
+        }
+    }
+
+    /// Appends `message` to the open conversation, dropping the oldest ones
+    /// past `MAX_MESSAGES`.
+    fn push_message(&mut self, message: MessageData) {
+        let messages = self.current_messages_mut();
+        messages.push(message);
+        // Pesan room yang dibuang masih bisa dimuat ulang dari riwayat server
+        if trim_oldest(messages) && self.active_dm.is_none() {
+            self.has_more_history = true;
+        }
+    }
+
+    fn push_system_message(&mut self, text: String) {
+        self.push_message(MessageData {
+            message: text,
+            timestamp: Some(js_sys::Date::now()),
+            kind: MessageKind::System,
+            ..Default::default()
+        });
+    }
+
+    /// [`merge_user_profiles`] against the current user list.
+    fn merge_users(
+        &self,
+        names: &[String],
+        profiles: &HashMap<String, Profile>,
+    ) -> Vec<UserProfile> {
+        let own = OwnProfile {
+            name: &self.username,
+            color: self.user.color.borrow().clone(),
+            avatar: self.user.avatar.borrow().clone(),
+        };
+        merge_user_profiles(&self.users, names, profiles, &self.statuses, &own)
+    }
+
+    /// Lists ourselves right away instead of waiting for the server's `Users`
+    /// reply, which replaces the list (and this entry) once it arrives.
+    fn show_self_pending(&mut self) {
+        if self.users.iter().any(|u| u.name == self.username) {
+            return;
+        }
+        let me = self.merge_users(std::slice::from_ref(&self.username), &HashMap::new());
+        self.users.extend(me);
+    }
+
+    fn announce_presence_changes(&mut self, names: &[String]) {
+        let joined: Vec<String> = names
+            .iter()
+            .filter(|name| **name != self.username && !self.users.iter().any(|u| u.name == **name))
+            .cloned()
+            .collect();
+        let left: Vec<String> = self
+            .users
+            .iter()
+            .map(|u| u.name.clone())
+            .filter(|name| *name != self.username && !names.contains(name))
+            .collect();
+        if joined.is_empty() && left.is_empty() {
+            return;
+        }
+        let near_bottom = self.is_near_bottom();
+        for name in joined {
+            self.push_system_message(t("user_joined", self.lang).replace("{name}", &name));
+        }
+        for name in left {
+            self.push_system_message(t("user_left", self.lang).replace("{name}", &name));
+        }
+        self.scroll_to_bottom = near_bottom;
+    }
+
+    /// Records when users missing from `names` were last seen, and forgets
+    /// the ones that came back.
+    fn track_departures(&mut self, names: &[String]) {
+        let now = js_sys::Date::now();
+        let left: Vec<(String, f64)> = self
+            .users
+            .iter()
+            .filter(|u| u.name != self.username && !names.contains(&u.name))
+            .map(|u| (u.name.clone(), now))
+            .collect();
+        self.recently_offline
+            .retain(|(name, _)| !names.contains(name) && !left.iter().any(|(l, _)| l == name));
+        self.recently_offline.splice(0..0, left);
+    }
+
+    // Unik antar client: waktu, urutan lokal, dan sedikit angka acak
+    fn next_message_id(&mut self) -> String {
+        self.id_counter += 1;
+        format!(
+            "{}-{}-{:04x}",
+            js_sys::Date::now() as u64,
+            self.id_counter,
+            (js_sys::Math::random() * 65536.0) as u32
+        )
+    }
+
+    fn render_message(
+        &self,
+        ctx: &Context<Self>,
+        m: &MessageData,
+        is_self: bool,
+        show_header: bool,
+    ) -> Html {
+        let Palette {
+            bg_tertiary,
+            text_primary,
+            text_secondary,
+            ..
+        } = PALETTE;
+        let query = self.active_search_query();
+        let user = self.users.iter().find(|u| u.name == m.from);
+        let user_color = user
+            .map(|u| u.color.clone())
+            .unwrap_or_else(|| Self::get_user_color(&m.from));
+        let user_avatar = user
+            .map(|u| u.avatar.clone())
+            .unwrap_or_else(|| m.from.clone());
+        let role = user.map(|u| u.role).unwrap_or_default();
+
+        // Pesan yang hanya berisi beberapa emoji tampil besar tanpa bubble
+        let jumbo = is_emoji_only(&m.message);
+        // Pesan sendiri rata kanan dengan bubble biru dan avatar di kanan
+        let (row_class, bubble_class, avatar_margin) = if jumbo {
+            if is_self {
+                ("flex justify-end", "flex-row-reverse", "ml-3")
+            } else {
+                ("flex", "", "mr-3")
+            }
+        } else if is_self {
+            // Sorotan seleksi bawaan (biru) tidak terlihat di bubble biru
+            (
+                "flex justify-end",
+                "flex-row-reverse bg-blue-600 selection:bg-white selection:text-blue-700",
+                "ml-3",
+            )
+        } else {
+            ("flex", bg_tertiary, "mr-3")
+        };
+        let accent = if m.id.is_some() && self.highlighted == m.id {
+            "ring-4 ring-blue-400"
+        } else if !is_self && mentions_user(&m.message, &self.username) {
+            "ring-2 ring-yellow-400"
+        } else {
+            ""
+        };
+        let (text_primary, text_secondary) = if is_self && !jumbo {
+            // text-blue-100 di atas biru kurang kontras untuk tema high contrast
+            let secondary = if self.theme == Theme::HighContrast {
+                "text-white"
+            } else {
+                "text-blue-100"
+            };
+            ("text-white", secondary)
+        } else {
+            (text_primary, text_secondary)
+        };
+
+        let message = if self.filter_enabled {
+            Cow::Owned(filter_text(&m.message, &FILTERED_WORDS))
+        } else {
+            Cow::Borrowed(m.message.as_str())
+        };
+        let data_image = is_image_data_url(&m.message);
+        let is_image = data_image || is_image_url(&m.message);
+        // Hanya pesan ber-id yang bisa dilipat, karena status buka/tutup disimpan per id
+        let cut = m.id.as_ref().filter(|_| !is_image).and_then(|id| {
+            collapse_point(&message, COLLAPSE_MAX_CHARS, COLLAPSE_MAX_LINES).map(|end| (id, end))
+        });
+        let collapsible = cut.map(|(id, _)| id);
+        let expanded = collapsible.is_some_and(|id| self.expanded_messages.contains(id));
+        let message = match cut {
+            Some((_, end)) if !expanded => Cow::Owned(format!("{}…", message[..end].trim_end())),
+            _ => message,
+        };
+
+        // Pesan sendiri meluncur dari bawah, pesan masuk cukup memudar
+        let enter_animation = match &m.id {
+            Some(id) if self.just_added.contains(id) => {
+                if is_self {
+                    "animate-slide-in"
+                } else {
+                    "animate-fade-in"
+                }
+            }
+            _ => "",
+        };
+        let oncontextmenu = m.id.clone().map(|id| {
+            ctx.link().callback(move |e: MouseEvent| {
+                e.prevent_default();
+                Msg::OpenContextMenu(id.clone(), e.client_x(), e.client_y())
+            })
+        });
+        let compact = self.density == Density::Compact;
+        let (margin, padding) = match (compact, show_header) {
+            (false, true) => ("mt-4", "py-4"),
+            (false, false) => ("mt-1", "py-2"),
+            (true, true) => ("mt-2", "py-2"),
+            (true, false) => ("mt-0.5", "py-1"),
+        };
+
+        html! {
+            <div id={m.id.as_deref().map(message_dom_id)} class={format!("{} {} {}", margin, row_class, enter_animation)}>
+                <div {oncontextmenu} class={format!("group relative flex items-start px-4 {} max-w-3xl {} {} rounded-lg {} transition-shadow select-text", padding, bubble_class, accent, if jumbo { "" } else { "shadow-sm" })}>
+                    {self.render_message_actions(ctx, m, is_self)}
+                    if show_header {
+                        {self.render_avatar(ctx, &m.from, &user_color, &user_avatar, &format!("w-8 h-8 text-xs {}", avatar_margin))}
+                    } else if !compact {
+                        // Spacer selebar avatar agar teks tetap sejajar
+                        <div class={format!("w-8 flex-shrink-0 {}", avatar_margin)}></div>
+                    }
+                    <div class="flex-1 min-w-0">
+                        if show_header {
+                            <div class="flex items-baseline mb-1">
+                                <span class={format!("font-medium text-sm {}", role.name_class().filter(|_| !is_self).unwrap_or(text_primary))}>
+                                    {highlight(&m.from, query)}
+                                </span>
+                                {render_role_badge(role, self.lang)}
+                                if m.to.is_some() {
+                                    <span class={format!("ml-2 text-xs {}", text_secondary)} title={t("private_message", self.lang)}>
+                                        {t("private_badge", self.lang)}
+                                    </span>
+                                }
+                                if let Some(ts) = m.timestamp {
+                                    <span
+                                        class={format!("ml-2 text-xs {}", text_secondary)}
+                                        title={Some(format_full_datetime(ts, self.lang)).filter(|full| !full.is_empty())}
+                                    >
+                                        {relative_time(ts, js_sys::Date::now(), self.lang)}
+                                    </span>
+                                }
+                            </div>
+                        }
+                        if let Some(reply_to) = &m.reply_to {
+                            {self.render_reply_quote(ctx, reply_to, text_secondary)}
+                        }
+                        <div class="flex items-end">
+                            <div class={format!("flex-1 min-w-0 whitespace-pre-wrap break-words {} {}", if jumbo { "text-4xl leading-tight" } else { self.font_size.class() }, text_primary)}>
+                                if data_image && !self.inline_images {
+                                    // Browser menolak membuka data URL di tab baru
+                                    <span>{format!("🖼️ {}", t("image", self.lang))}</span>
+                                } else if is_image && !self.inline_images {
+                                    <a
+                                        href={m.message.clone()}
+                                        target="_blank"
+                                        rel="noopener noreferrer"
+                                        class="underline hover:opacity-75"
+                                        title={m.message.clone()}
+                                    >
+                                        {format!("🖼️ {}", url_filename(&m.message))}
+                                    </a>
+                                } else if is_image && !self.failed_images.contains(&m.message) {
+                                    <img
+                                        class="mt-2 max-w-xs rounded-lg"
+                                        src={m.message.clone()}
+                                        alt={t("image", self.lang)}
+                                        loading="lazy"
+                                        onerror={ctx.link().callback({
+                                            let url = m.message.clone();
+                                            move |_| Msg::ImageFailed(url.clone())
+                                        })}
+                                    />
+                                } else if let Some(action) = action_text(&message) {
+                                    // Pesan /me ditampilkan sebagai orang ketiga
+                                    <span class="italic">
+                                        {highlight(&m.from, query)}{" "}{render_markdown(action, query)}
+                                    </span>
+                                } else {
+                                    {render_message_body(&message, query, &ctx.link().callback(Msg::CopyMessage), self.copied.as_deref())}
+                                }
+                                if let Some(id) = collapsible.cloned() {
+                                    <button
+                                        type="button"
+                                        onclick={ctx.link().callback(move |_| Msg::ToggleExpanded(id.clone()))}
+                                        class={format!("block mt-1 text-xs font-medium underline hover:opacity-75 select-none {}", text_secondary)}
+                                    >
+                                        {t(if expanded { "show_less" } else { "show_more" }, self.lang)}
+                                    </button>
+                                }
+                            </div>
+                            if m.id.as_ref().is_some_and(|id| self.pinned_messages.contains(id)) {
+                                <span class={format!("pl-2 text-xs select-none {}", text_secondary)} title={t("pinned", self.lang)}>{"📌"}</span>
+                            }
+                            if m.edited {
+                                <span class={format!("pl-2 text-xs whitespace-nowrap {}", text_secondary)}>{t("edited", self.lang)}</span>
+                            }
+                            {
+                                match m.status.map(DeliveryStatus::icon) {
+                                    Some((icon, label)) => html! {
+                                        <span class={format!("pl-2 text-xs select-none {}", text_secondary)} title={t(label, self.lang)}>
+                                            {icon}
+                                        </span>
+                                    },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                        {self.render_link_preview(ctx, m)}
+                        {self.render_reactions(ctx, m, is_self)}
+                        {self.render_read_receipts(ctx, m)}
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    // Kutipan pesan yang dibalas; klik untuk melompat ke pesan aslinya
+    fn render_reply_quote(&self, ctx: &Context<Self>, reply_to: &str, text_color: &str) -> Html {
+        let original = self
+            .current_messages()
+            .iter()
+            .find(|m| m.id.as_deref() == Some(reply_to));
+        match original {
+            Some(original) => {
+                let onclick = {
+                    let id = reply_to.to_string();
+                    ctx.link().callback(move |_| Msg::JumpToMessage(id.clone()))
+                };
+                html! {
+                    <button
+                        type="button"
+                        {onclick}
+                        class={format!("block w-full mb-1 pl-2 text-left text-xs border-l-2 border-current opacity-80 hover:opacity-100 truncate select-none {}", text_color)}
+                        title={t("jump_to_message", self.lang)}
+                    >
+                        <span class="font-medium">{format!("↩ {}: ", original.from)}</span>
+                        {snippet(&original.message, REPLY_SNIPPET_CHARS)}
+                    </button>
+                }
+            }
+            None => html! {
+                <div class={format!("mb-1 pl-2 text-xs italic border-l-2 border-current opacity-60 select-none {}", text_color)}>
+                    {t("original_not_loaded", self.lang)}
+                </div>
+            },
+        }
+    }
+
+    // Avatar kecil user yang terakhir membaca sampai pesan ini
+    fn render_read_receipts(&self, ctx: &Context<Self>, m: &MessageData) -> Html {
+        let id = match &m.id {
+            Some(id) => id,
+            None => return html! {},
+        };
+        let mut readers: Vec<&str> = self
+            .read_receipts
+            .iter()
+            .filter(|(user, read)| *read == id && **user != m.from)
+            .map(|(user, _)| user.as_str())
+            .collect();
+        if readers.is_empty() {
+            return html! {};
+        }
+        readers.sort_unstable();
+
+        html! {
+            <div class="flex justify-end mt-1 -space-x-1 select-none" title={t("seen_by", self.lang).replace("{names}", &readers.join(", "))}>
+                {
+                    readers.into_iter().map(|name| {
+                        let user = self.users.iter().find(|u| u.name == name);
+                        let color = user.map_or_else(|| Self::get_user_color(name), |u| u.color.clone());
+                        let avatar = user.map_or(name, |u| u.avatar.as_str());
+                        self.render_avatar(ctx, name, &color, avatar, "w-4 h-4 text-[8px] ring-1 ring-white")
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    // Kartu preview di bawah pesan; tanpa preview hanya link biasa yang tampil
+    fn render_link_preview(&self, ctx: &Context<Self>, m: &MessageData) -> Html {
+        let preview = match first_link(&m.message).and_then(|url| self.link_previews.get(url)) {
+            Some(PreviewState::Ready(preview)) => preview,
+            _ => return html! {},
+        };
+        let Palette {
+            bg_secondary,
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let image = preview
+            .image
+            .as_ref()
+            .filter(|_| self.inline_images)
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+            .filter(|url| !self.failed_images.contains(*url));
+
+        html! {
+            <a
+                href={preview.url.clone()}
+                target="_blank"
+                rel="noopener noreferrer"
+                class={format!("flex mt-2 max-w-sm overflow-hidden border rounded-lg hover:opacity-90 {} {}", bg_secondary, border_color)}
+            >
+                if let Some(image) = image {
+                    <img
+                        src={image.clone()}
+                        alt=""
+                        loading="lazy"
+                        class="flex-shrink-0 object-cover w-20 h-20"
+                        onerror={ctx.link().callback({
+                            let url = image.clone();
+                            move |_| Msg::ImageFailed(url.clone())
+                        })}
+                    />
+                }
+                <div class="min-w-0 p-2">
+                    if let Some(title) = &preview.title {
+                        <div class={format!("text-sm font-medium truncate {}", text_primary)}>{title.clone()}</div>
+                    }
+                    if let Some(description) = &preview.description {
+                        <div class={format!("text-xs line-clamp-2 {}", text_secondary)}>{description.clone()}</div>
+                    }
+                </div>
+            </a>
+        }
+    }
+
+    fn render_reactions(&self, ctx: &Context<Self>, m: &MessageData, is_self: bool) -> Html {
+        let id = match &m.id {
+            Some(id) if !m.reactions.is_empty() => id,
+            _ => return html! {},
+        };
+        let Palette {
+            bg_secondary,
+            text_primary,
+            border_color,
+            ..
+        } = PALETTE;
+        let justify = if is_self { "justify-end" } else { "" };
+
+        html! {
+            <div class={format!("flex flex-wrap gap-1 mt-2 select-none {}", justify)}>
+                {
+                    reaction::sorted(&m.reactions).into_iter().map(|(emoji, users)| {
+                        // Reaksi milik sendiri ditandai biru
+                        let chip_color = if users.contains(&self.username) {
+                            "border-blue-400 bg-blue-100 text-blue-800".to_string()
+                        } else {
+                            format!("{} {} {}", bg_secondary, border_color, text_primary)
+                        };
+                        let onclick = {
+                            let (id, emoji) = (id.clone(), emoji.to_string());
+                            ctx.link().callback(move |_| Msg::ToggleReaction { id: id.clone(), emoji: emoji.clone() })
+                        };
+                        html! {
+                            <button
+                                {onclick}
+                                title={users.join(", ")}
+                                class={format!("px-2 py-0.5 text-xs border rounded-full {}", chip_color)}
+                            >
+                                {format!("{} {}", emoji, users.len())}
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    // Tombol aksi yang muncul saat bubble di-hover
+    fn render_message_actions(&self, ctx: &Context<Self>, m: &MessageData, is_self: bool) -> Html {
+        let Palette {
+            bg_secondary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let copied = self.copied.as_ref() == Some(&m.message);
+        let pinned =
+            m.id.as_ref()
+                .is_some_and(|id| self.pinned_messages.contains(id));
+        let pin_key = if pinned {
+            "unpin_message"
+        } else {
+            "pin_message"
+        };
+        let position = if is_self { "left-2" } else { "right-2" };
+        let react_label = |emoji: &str| t("react_with", self.lang).replace("{emoji}", emoji);
+        let visibility = if copied {
+            "flex"
+        } else {
+            "hidden group-hover:flex"
+        };
+
+        html! {
+            // select-none agar tombol aksi tidak ikut tersalin saat menyeleksi pesan
+            <div class={format!("absolute -top-3 {} {} items-center px-1 space-x-1 text-xs border rounded-lg shadow-sm select-none {} {} {}", position, visibility, bg_secondary, border_color, text_secondary)}>
+                if let Some(id) = m.id.clone() {
+                    {
+                        QUICK_REACTIONS.iter().map(|emoji| {
+                            let id = id.clone();
+                            html! {
+                                <button
+                                    onclick={ctx.link().callback(move |_| Msg::ToggleReaction { id: id.clone(), emoji: emoji.to_string() })}
+                                    class="px-1 py-0.5 hover:opacity-75"
+                                    title={react_label(emoji)}
+                                    aria-label={react_label(emoji)}
+                                >
+                                    {*emoji}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                }
+                if let Some(id) = m.id.clone() {
+                    <button
+                        onclick={ctx.link().callback(move |_| Msg::ReplyTo(Some(id.clone())))}
+                        class="px-1 py-0.5 hover:opacity-75"
+                        title={t("reply", self.lang)}
+                        aria-label={t("reply", self.lang)}
+                    >
+                        {"↩️"}
+                    </button>
+                }
+                if let Some(id) = m.id.clone() {
+                    <button
+                        onclick={ctx.link().callback(move |_| Msg::TogglePin(id.clone()))}
+                        class="px-1 py-0.5 hover:opacity-75"
+                        title={t(pin_key, self.lang)}
+                        aria-label={t(pin_key, self.lang)}
+                    >
+                        {"📌"}
+                    </button>
+                }
+                if copied {
+                    <span class="px-1 py-0.5" role="status">{t("copied", self.lang)}</span>
+                } else if clipboard::is_supported() {
+                    <button
+                        onclick={ctx.link().callback({
+                            let text = m.message.clone();
+                            move |_| Msg::CopyMessage(text.clone())
+                        })}
+                        class="px-1 py-0.5 hover:opacity-75"
+                        title={t("copy_message", self.lang)}
+                        aria-label={t("copy_message", self.lang)}
+                    >
+                        {"📋"}
+                    </button>
+                }
+                if !is_self {
+                    <button
+                        onclick={ctx.link().callback({
+                            let name = m.from.clone();
+                            move |_| Msg::ToggleMuteUser(name.clone())
+                        })}
+                        class="px-1 py-0.5 hover:opacity-75"
+                        title={t("mute_name", self.lang).replace("{name}", &m.from)}
+                        aria-label={t("mute_name", self.lang).replace("{name}", &m.from)}
+                    >
+                        {"🔇"}
+                    </button>
+                }
+                if is_self {
+                    if let Some(id) = m.id.clone() {
+                        <button
+                            onclick={ctx.link().callback(move |_| Msg::DeleteMessage(id.clone()))}
+                            class="px-1 py-0.5 hover:opacity-75"
+                            title={t("delete_message", self.lang)}
+                            aria-label={t("delete_message", self.lang)}
+                        >
+                            {"🗑️"}
+                        </button>
+                    }
+                }
+            </div>
+        }
+    }
+
+    /// Renders `name`'s avatar image, or a colored initial if it can't load.
+    /// `class` sets the size, text size and margin.
+    fn render_avatar(
+        &self,
+        ctx: &Context<Self>,
+        name: &str,
+        color: &str,
+        avatar: &str,
+        class: &str,
+    ) -> Html {
+        let url = avatar_url(avatar);
+        let style = format!("background-color: {}", color);
+        if self.failed_images.contains(&url) {
+            let style = format!("{}; color: {}", style, contrast_text_color(color));
+            return html! {
+                <div {style} class={format!("rounded-full flex items-center justify-center font-bold flex-shrink-0 select-none {}", class)}>
+                    {avatar_initial(name)}
+                </div>
+            };
+        }
+        let onerror = {
+            let url = url.clone();
+            ctx.link().callback(move |_| Msg::ImageFailed(url.clone()))
+        };
+
+        html! {
+            <img
+                src={url}
+                alt={name.to_string()}
+                {style}
+                {onerror}
+                class={format!("rounded-full object-cover flex-shrink-0 select-none {}", class)}
+            />
+        }
+    }
+
+    // Tombol ⋯ di baris user sidebar beserta menu Kick/Ban
+    fn render_moderation_menu(&self, ctx: &Context<Self>, name: &str) -> Html {
+        let Palette {
+            bg_secondary,
+            text_primary,
+            text_secondary,
+            border_color,
+            hover_bg,
+            ..
+        } = PALETTE;
+        let open = self.moderation_menu.as_deref() == Some(name);
+        let toggle = {
+            let name = name.to_string();
+            ctx.link().callback(move |e: MouseEvent| {
+                e.stop_propagation();
+                Msg::ToggleModerationMenu(name.clone())
+            })
+        };
+        let item = |action: ModAction| {
+            let name = name.to_string();
+            let onclick = ctx.link().callback(move |e: MouseEvent| {
+                e.stop_propagation();
+                Msg::RequestModeration(action, name.clone())
+            });
+            html! {
+                <button
+                    type="button"
+                    {onclick}
+                    role="menuitem"
+                    class={format!("block w-full px-3 py-1 text-left text-sm text-red-500 {}", hover_bg)}
+                >
+                    {t(action.key(), self.lang)}
+                </button>
+            }
+        };
+
+        html! {
+            <>
+                <button
+                    type="button"
+                    onclick={toggle}
+                    onkeydown={Callback::from(|e: KeyboardEvent| e.stop_propagation())}
+                    class={format!("ml-1 p-1 rounded {} {}", hover_bg, text_secondary)}
+                    title={t("moderate", self.lang)}
+                    aria-label={t("moderate_user", self.lang).replace("{name}", name)}
+                    aria-haspopup="menu"
+                    aria-expanded={open.to_string()}
+                >
+                    {"⋯"}
+                </button>
+                if open {
+                    <div
+                        role="menu"
+                        class={format!("absolute right-2 top-full z-20 -mt-2 py-1 border rounded-lg shadow-lg {} {} {}", bg_secondary, border_color, text_primary)}
+                    >
+                        {item(ModAction::Kick)}
+                        {item(ModAction::Ban)}
+                    </div>
+                }
+            </>
+        }
+    }
+
+    fn render_moderation_confirm(&self, ctx: &Context<Self>) -> Html {
+        let (action, name) = match &self.confirm_moderation {
+            Some(pending) => pending,
+            None => return html! {},
+        };
+        let Palette {
+            bg_secondary,
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let title = t(&format!("confirm_{}", action.key()), self.lang).replace("{name}", name);
+
+        html! {
+            <>
+                <div class="fixed inset-0 z-40 bg-black bg-opacity-25" onclick={ctx.link().callback(|_| Msg::CancelModeration)}></div>
+                <div
+                    role="alertdialog"
+                    aria-modal="true"
+                    aria-label={title.clone()}
+                    class={format!("fixed top-1/2 left-1/2 z-50 w-80 p-4 space-y-4 transform -translate-x-1/2 -translate-y-1/2 border rounded-lg shadow-xl {} {}", bg_secondary, border_color)}
+                >
+                    <div class={format!("text-lg font-semibold {}", text_primary)}>
+                        {title}
+                    </div>
+                    <div class={format!("text-sm {}", text_secondary)}>
+                        {t(&format!("{}_hint", action.key()), self.lang)}
+                    </div>
+                    <div class="flex justify-end space-x-2">
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::CancelModeration)}
+                            class={format!("px-3 py-1 text-sm rounded-lg border {} {}", border_color, text_primary)}
+                        >
+                            {t("cancel", self.lang)}
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ConfirmModeration)}
+                            class="px-3 py-1 text-sm font-medium text-white bg-red-500 hover:bg-red-600 rounded-lg"
+                        >
+                            {t(action.key(), self.lang)}
+                        </button>
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    // Menggantikan seluruh tampilan setelah kita dikeluarkan dari chat
+    fn render_removed(&self, action: ModAction) -> Html {
+        let Palette {
+            bg_primary,
+            text_primary,
+            text_secondary,
+            ..
+        } = PALETTE;
+
+        html! {
+            <div class={format!("flex items-center justify-center w-full h-screen {} {}", self.theme.class(), bg_primary)}>
+                <div class="text-center" role="alert">
+                    <div class="text-4xl mb-4">{"🚫"}</div>
+                    <div class={format!("text-lg font-semibold {}", text_primary)}>
+                        {t(&format!("removed_{}", action.key()), self.lang)}
+                    </div>
+                    <div class={format!("text-sm mt-2 {}", text_secondary)}>
+                        {t("removed_hint", self.lang)}
+                    </div>
+                    <div class="mt-4 text-sm text-blue-500 underline">
+                        <Link<Route> to={Route::Login}>{t("back_to_login", self.lang)}</Link<Route>>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_undo_snackbar(&self, ctx: &Context<Self>) -> Html {
+        if self.cleared_backup.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div class="fixed bottom-24 left-1/2 z-40 flex items-center px-4 py-3 space-x-4 text-sm text-white transform -translate-x-1/2 bg-gray-900 rounded-lg shadow-lg">
+                <span>{t("cleared_count", self.lang).replace("{count}", &self.cleared_backup.len().to_string())}</span>
+                <button
+                    onclick={ctx.link().callback(|_| Msg::UndoClear)}
+                    class="font-semibold text-blue-300 uppercase hover:text-blue-200"
+                >
+                    {t("undo", self.lang)}
+                </button>
+            </div>
+        }
+    }
+
+    fn render_settings_panel(&self, ctx: &Context<Self>) -> Html {
+        if !self.settings_open {
+            return html! {};
+        }
+        let Palette {
+            bg_secondary,
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let option_class = |selected: bool| {
+            if selected {
+                "flex-1 px-3 py-1 text-sm rounded-lg bg-blue-600 text-white".to_string()
+            } else {
+                format!(
+                    "flex-1 px-3 py-1 text-sm rounded-lg border {} {}",
+                    border_color, text_primary
+                )
+            }
+        };
+
+        html! {
+            <>
+                // Klik di luar panel menutupnya
+                <div class="fixed inset-0 z-30 bg-black bg-opacity-25" onclick={ctx.link().callback(|_| Msg::ToggleSettings)}></div>
+                <div
+                    role="dialog"
+                    aria-label={t("settings", self.lang)}
+                    class={format!("fixed top-0 right-0 z-40 h-full w-72 p-4 space-y-6 border-l shadow-xl {} {}", bg_secondary, border_color)}
+                >
+                    <div class="flex items-center justify-between">
+                        <h2 class={format!("text-lg font-semibold {}", text_primary)}>{t("settings", self.lang)}</h2>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleSettings)}
+                            class={text_secondary}
+                            title={t("close", self.lang)}
+                            aria-label={t("close_settings", self.lang)}
+                        >
+                            {"✕"}
+                        </button>
+                    </div>
+
+                    <div>
+                        <div class={format!("mb-2 text-xs font-medium uppercase {}", text_secondary)}>{t("font_size", self.lang)}</div>
+                        <div class="flex space-x-2">
+                            {
+                                FontSize::ALL.into_iter().map(|size| html! {
+                                    <button
+                                        onclick={ctx.link().callback(move |_| Msg::SetFontSize(size))}
+                                        class={option_class(size == self.font_size)}
+                                        aria-pressed={(size == self.font_size).to_string()}
+                                    >
+                                        {size.label(self.lang)}
+                                    </button>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+
+                    <div>
+                        <div class={format!("mb-2 text-xs font-medium uppercase {}", text_secondary)}>{t("message_density", self.lang)}</div>
+                        <div class="flex space-x-2">
+                            {
+                                Density::ALL.into_iter().map(|density| html! {
+                                    <button
+                                        onclick={ctx.link().callback(move |_| Msg::SetDensity(density))}
+                                        class={option_class(density == self.density)}
+                                        aria-pressed={(density == self.density).to_string()}
+                                    >
+                                        {density.label(self.lang)}
+                                    </button>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+
+                    <div>
+                        <div class={format!("mb-2 text-xs font-medium uppercase {}", text_secondary)}>{t("language", self.lang)}</div>
+                        <div class="flex space-x-2">
+                            {
+                                Lang::ALL.into_iter().map(|lang| html! {
+                                    <button
+                                        onclick={ctx.link().callback(move |_| Msg::SetLang(lang))}
+                                        class={option_class(lang == self.lang)}
+                                        aria-pressed={(lang == self.lang).to_string()}
+                                        lang={lang.key()}
+                                    >
+                                        {lang.label()}
+                                    </button>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+
+                    <div>
+                        <div class={format!("mb-2 text-xs font-medium uppercase {}", text_secondary)}>{t("theme", self.lang)}</div>
+                        <div class="flex space-x-2">
+                            {
+                                Theme::ALL.into_iter().map(|theme| html! {
+                                    <button
+                                        onclick={ctx.link().callback(move |_| Msg::SetTheme(theme))}
+                                        class={option_class(theme == self.theme)}
+                                        aria-pressed={(theme == self.theme).to_string()}
+                                    >
+                                        {theme.label(self.lang)}
+                                    </button>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+
+                    <div class="space-y-2">
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleMute)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={(!self.muted).to_string()}
+                        >
+                            <span>{t("notification_sound", self.lang)}</span>
+                            <span>{if self.muted { format!("🔕 {}", t("off", self.lang)) } else { format!("🔔 {}", t("on", self.lang)) }}</span>
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleWordFilter)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={(self.filter_enabled).to_string()}
+                            title={t("word_filter_hint", self.lang)}
+                        >
+                            <span>{t("word_filter", self.lang)}</span>
+                            <span>{if self.filter_enabled { format!("🙈 {}", t("on", self.lang)) } else { t("off", self.lang).to_string() }}</span>
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleInlineImages)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={self.inline_images.to_string()}
+                            title={t("show_images_hint", self.lang)}
+                        >
+                            <span>{t("show_images", self.lang)}</span>
+                            <span>{if self.inline_images { format!("🖼️ {}", t("on", self.lang)) } else { t("off", self.lang).to_string() }}</span>
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleOptimisticSend)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={self.optimistic_send.to_string()}
+                            title={t("instant_send_hint", self.lang)}
+                        >
+                            <span>{t("instant_send", self.lang)}</span>
+                            <span>{if self.optimistic_send { format!("⚡ {}", t("on", self.lang)) } else { t("off", self.lang).to_string() }}</span>
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleBatchInbound)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={self.batch_inbound.to_string()}
+                            title={t("batch_incoming_hint", self.lang)}
+                        >
+                            <span>{t("batch_incoming", self.lang)}</span>
+                            <span>{if self.batch_inbound { format!("📦 {}", t("on", self.lang)) } else { t("off", self.lang).to_string() }}</span>
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::ToggleReactionAlerts)}
+                            class={format!("flex items-center justify-between w-full text-sm {}", text_primary)}
+                            role="switch"
+                            aria-checked={self.reaction_alerts.to_string()}
+                            title={t("reaction_alerts_hint", self.lang)}
+                        >
+                            <span>{t("reaction_alerts", self.lang)}</span>
+                            <span>{if self.reaction_alerts { format!("👍 {}", t("on", self.lang)) } else { t("off", self.lang).to_string() }}</span>
+                        </button>
+                    </div>
+
+                    <div>
+                        <div class={format!("mb-2 text-xs font-medium uppercase {}", text_secondary)}>{t("chat_history", self.lang)}</div>
+                        <div class="flex space-x-2">
+                            <select
+                                onchange={ctx.link().batch_callback(|e: Event| {
+                                    let select: HtmlSelectElement = e.target_unchecked_into();
+                                    ExportFormat::from_key(&select.value()).map(Msg::SetExportFormat)
+                                })}
+                                class={format!("flex-1 px-2 py-1 text-sm border rounded-lg bg-transparent {} {}", border_color, text_primary)}
+                                title={t("export_format", self.lang)}
+                            >
+                                {
+                                    ExportFormat::ALL.into_iter().map(|fmt| html! {
+                                        <option value={fmt.key()} selected={fmt == self.export_format}>
+                                            {fmt.label(self.lang)}
+                                        </option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::ExportChat)}
+                                disabled={self.current_messages().is_empty()}
+                                class="flex-1 px-3 py-1 text-sm rounded-lg bg-blue-600 hover:bg-blue-700 text-white disabled:opacity-50"
+                            >
+                                {t("export", self.lang)}
+                            </button>
+                        </div>
+                        <div class="flex mt-2 space-x-2">
+                            <select
+                                onchange={ctx.link().batch_callback(|e: Event| {
+                                    let select: HtmlSelectElement = e.target_unchecked_into();
+                                    ImportMode::from_key(&select.value()).map(Msg::SetImportMode)
+                                })}
+                                class={format!("flex-1 px-2 py-1 text-sm border rounded-lg bg-transparent {} {}", border_color, text_primary)}
+                                title={t("import_mode", self.lang)}
+                            >
+                                {
+                                    ImportMode::ALL.into_iter().map(|mode| html! {
+                                        <option value={mode.key()} selected={mode == self.import_mode}>
+                                            {mode.label(self.lang)}
+                                        </option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            <label class={format!("flex-1 px-3 py-1 text-sm text-center border rounded-lg cursor-pointer {} {}", border_color, text_primary)}>
+                                {t("import", self.lang)}
+                                <input
+                                    type="file"
+                                    accept="application/json,.json"
+                                    class="hidden"
+                                    onchange={ctx.link().batch_callback(|e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        let file = input.files().and_then(|files| files.get(0));
+                                        // Kosongkan agar file yang sama bisa dipilih lagi
+                                        input.set_value("");
+                                        file.map(Msg::ImportFile)
+                                    })}
+                                />
+                            </label>
+                        </div>
+                        if let Some(error) = &self.import_error {
+                            <p class="mt-2 text-xs text-red-500">{error.clone()}</p>
+                        }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    fn render_profile_settings(&self, ctx: &Context<Self>) -> Html {
+        let Palette {
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let custom_color = self.user.color.borrow().clone();
+        let avatar = self
+            .user
+            .avatar
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| self.username.clone());
+        let color = custom_color
+            .clone()
+            .unwrap_or_else(|| Self::get_user_color(&self.username));
+        let onchange = ctx.link().callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::SetAvatarColor(Some(input.value()))
+        });
+        let onreset = ctx.link().callback(|_| Msg::SetAvatarColor(None));
+
+        html! {
+            <div class={format!("flex items-center p-4 border-t {}", border_color)}>
+                {self.render_avatar(ctx, &self.username, &color, &avatar, "w-10 h-10 text-sm mr-3")}
+                <div class="flex-1 min-w-0">
+                    <div class={format!("font-medium truncate {}", text_primary)}>{self.username.clone()}</div>
+                    if custom_color.is_some() {
+                        <button onclick={onreset} class={format!("text-xs underline {}", text_secondary)}>
+                            {t("reset_color", self.lang)}
+                        </button>
+                    } else {
+                        <div class={format!("text-xs {}", text_secondary)}>{t("avatar_color", self.lang)}</div>
+                    }
+                    <button onclick={ctx.link().callback(|_| Msg::SignOut)} class={format!("block text-xs underline {}", text_secondary)}>
+                        {t("sign_out", self.lang)}
+                    </button>
+                </div>
+                <input
+                    type="color"
+                    value={color}
+                    {onchange}
+                    class="w-8 h-8 p-0 border-0 rounded cursor-pointer bg-transparent"
+                    title={t("choose_avatar_color", self.lang)}
+                />
+            </div>
+        }
+    }
+
+    /// Users who left the room in the last `RECENTLY_OFFLINE_MS`, with when
+    /// they were last seen.
+    fn render_recently_offline(&self) -> Html {
+        if self.recently_offline.is_empty() {
+            return html! {};
+        }
+        let Palette {
+            text_primary,
+            text_secondary,
+            ..
+        } = PALETTE;
+        let lang = self.lang;
+        let now = js_sys::Date::now();
+
+        html! {
+            <div class="px-3 pb-3">
+                <h3 class={format!("px-3 mt-2 mb-1 text-xs font-medium uppercase {}", text_secondary)}>
+                    {t("recently_offline", lang)}
+                </h3>
+                <ul aria-label={t("recently_offline", lang)}>
+                    {
+                        self.recently_offline.iter().map(|(name, seen)| html! {
+                            <li class="flex items-center justify-between px-3 py-1 text-sm opacity-75">
+                                <span class={format!("truncate {}", text_primary)}>{name.clone()}</span>
+                                <span class={format!("ml-2 text-xs whitespace-nowrap {}", text_secondary)}>
+                                    {t("last_seen", lang).replace("{time}", &relative_time(*seen, now, lang))}
+                                </span>
+                            </li>
+                        }).collect::<Html>()
+                    }
+                </ul>
+            </div>
+        }
+    }
+
+    /// Renders `messages[range]`; earlier messages are still consulted for
+    /// grouping and date separators.
+    fn render_message_rows(
+        &self,
+        ctx: &Context<Self>,
+        messages: &[&MessageData],
+        range: Range<usize>,
+    ) -> Html {
+        range
+            .map(|i| {
+                let m = messages[i];
+                let previous = i.checked_sub(1).map(|p| messages[p]);
+                let show_header = previous.is_none_or(|prev| starts_new_group(prev, m));
+                // Tanpa timestamp tidak ada pemisah tanggal
+                let separator = match (previous.map(|prev| prev.timestamp), m.timestamp) {
+                    (None, Some(ts)) => Some(ts),
+                    (Some(Some(prev)), Some(ts)) if !same_local_day(prev, ts) => Some(ts),
+                    _ => None,
+                };
+                let after_marker = self.unread_marker.is_some()
+                    && previous.is_some_and(|prev| prev.id == self.unread_marker);
+                html! {
+                    <>
+                        if let Some(ts) = separator {
+                            {self.render_day_separator(ts)}
+                        }
+                        if after_marker {
+                            {self.render_new_messages_divider()}
+                        }
+                        if m.kind == MessageKind::System {
+                            {self.render_system_message(m)}
+                        } else if self.muted_users.contains(&m.from) {
+                            {self.render_muted_message(ctx, m)}
+                        } else {
+                            {self.render_message(ctx, m, m.from == self.username, show_header)}
+                        }
+                    </>
+                }
+            })
+            .collect::<Html>()
+    }
+
+    // Panel hasil pencarian server, terpisah dari daftar pesan biasa
+    fn render_server_search(&self, ctx: &Context<Self>) -> Html {
+        let Palette {
+            bg_secondary,
+            text_primary,
+            text_secondary,
+            border_color,
+            hover_bg,
+            ..
+        } = PALETTE;
+        let lang = self.lang;
+        let status = |text: &str| {
+            html! {
+                <li class={format!("px-4 py-3 text-sm text-center {}", text_secondary)} role="status">{text.to_string()}</li>
+            }
+        };
+        let body = match &self.server_search {
+            ServerSearch::Idle => return html! {},
+            ServerSearch::Loading(_) => status(t("searching", lang)),
+            ServerSearch::Loaded(_, results) if results.is_empty() => {
+                status(t("no_search_results", lang))
+            }
+            ServerSearch::Loaded(query, results) => {
+                let now = js_sys::Date::now();
+                let current = self.current_messages();
+                results.iter().map(|m| {
+                    // Hanya pesan yang sudah dimuat yang bisa dituju
+                    let loaded = m.id.as_ref().filter(|id| current.iter().any(|c| c.id.as_ref() == Some(*id)));
+                    let onclick = loaded.cloned().map(|id| {
+                        ctx.link().callback(move |_| Msg::OpenSearchResult(id.clone()))
+                    });
+                    html! {
+                        <li>
+                            <button
+                                type="button"
+                                {onclick}
+                                disabled={loaded.is_none()}
+                                title={if loaded.is_some() { t("jump_to_message", lang) } else { t("search_result_not_loaded", lang) }}
+                                class={format!("block w-full px-4 py-2 text-left text-sm {} disabled:cursor-default disabled:hover:bg-transparent {}", hover_bg, text_primary)}
+                            >
+                                <div class={format!("text-xs {}", text_secondary)}>
+                                    <span class="font-medium">{m.from.clone()}</span>
+                                    if let Some(ts) = m.timestamp {
+                                        {format!(" · {}", relative_time(ts, now, lang))}
+                                    }
+                                </div>
+                                <div class="truncate">{highlight(&snippet(&m.message, REPLY_SNIPPET_CHARS), query)}</div>
+                            </button>
+                        </li>
+                    }
+                }).collect::<Html>()
+            }
+        };
+
+        html! {
+            <div
+                class={format!("max-h-64 overflow-y-auto border-b {} {}", bg_secondary, border_color)}
+                role="region"
+                aria-label={t("search_results", lang)}
+                aria-busy={matches!(self.server_search, ServerSearch::Loading(_)).to_string()}
+            >
+                <ul>{body}</ul>
+            </div>
+        }
+    }
+
+    // Menu klik kanan pada pesan, digeser agar tidak keluar dari viewport
+    fn render_context_menu(&self, ctx: &Context<Self>) -> Html {
+        let (id, x, y) = match &self.context_menu {
+            Some(menu) => menu,
+            None => return html! {},
+        };
+        let m = match self
+            .current_messages()
+            .iter()
+            .find(|m| m.id.as_ref() == Some(id))
+        {
+            Some(m) => m,
+            None => return html! {},
+        };
+        let Palette {
+            bg_secondary,
+            text_primary,
+            border_color,
+            hover_bg,
+            ..
+        } = PALETTE;
+        let is_self = m.from == self.username;
+        let pinned = self.pinned_messages.contains(id);
+
+        let item = |action: ContextAction, danger: bool| {
+            html! {
+                <button
+                    type="button"
+                    role="menuitem"
+                    onclick={ctx.link().callback(move |_| Msg::ContextAction(action))}
+                    class={format!("block w-full px-3 py-1.5 text-left text-sm {} {}", hover_bg, if danger { "text-red-500" } else { text_primary })}
+                >
+                    {action.label(pinned, self.lang)}
+                </button>
+            }
+        };
+        let mut items = vec![item(ContextAction::Reply, false)];
+        if clipboard::is_supported() {
+            items.push(item(ContextAction::Copy, false));
+        }
+        items.push(item(ContextAction::Pin, false));
+        if is_self {
+            items.push(item(ContextAction::Delete, true));
+        }
+
+        let (viewport_width, viewport_height) = web_sys::window()
+            .map(|w| {
+                let size = |v: Result<JsValue, JsValue>| {
+                    v.ok().and_then(|v| v.as_f64()).unwrap_or(0.0) as i32
+                };
+                (size(w.inner_width()), size(w.inner_height()))
+            })
+            .unwrap_or_default();
+        // Baris reaksi setinggi kira-kira satu item
+        let height = (items.len() as i32 + 1) * CONTEXT_MENU_ITEM_HEIGHT + 8;
+        let left = (*x).min(viewport_width - CONTEXT_MENU_WIDTH).max(0);
+        let top = (*y).min(viewport_height - height).max(0);
+        let close = ctx.link().callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::CloseContextMenu
+        });
+
+        html! {
+            <>
+                <div class="fixed inset-0 z-40" onclick={close.clone()} oncontextmenu={close}></div>
+                <div
+                    role="menu"
+                    aria-label={t("message_actions", self.lang)}
+                    class={format!("fixed z-50 py-1 border rounded-lg shadow-lg select-none {} {}", bg_secondary, border_color)}
+                    style={format!("left: {}px; top: {}px; width: {}px", left, top, CONTEXT_MENU_WIDTH)}
+                >
+                    <div class="flex justify-around px-2 pb-1">
+                        {
+                            QUICK_REACTIONS.iter().map(|emoji| {
+                                html! {
+                                    <button
+                                        type="button"
+                                        role="menuitem"
+                                        onclick={ctx.link().callback(move |_| Msg::ContextAction(ContextAction::React(emoji)))}
+                                        class="px-1 py-0.5 hover:opacity-75"
+                                        title={t("react_with", self.lang).replace("{emoji}", emoji)}
+                                        aria-label={t("react_with", self.lang).replace("{emoji}", emoji)}
+                                    >
+                                        {*emoji}
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                    {items}
+                </div>
+            </>
+        }
+    }
+
+    // Pesan yang disematkan di percakapan ini; tertutup hanya menampilkan yang terbaru
+    fn render_pinned_banner(&self, ctx: &Context<Self>) -> Html {
+        let Palette {
+            bg_tertiary,
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let messages = self.current_messages();
+        // Pin untuk pesan yang belum dimuat atau ada di room lain tidak ditampilkan
+        let pinned: Vec<(&String, &MessageData)> = self
+            .pinned_messages
+            .iter()
+            .rev()
+            .filter_map(|id| {
+                messages
+                    .iter()
+                    .find(|m| m.id.as_ref() == Some(id))
+                    .map(|m| (id, m))
+            })
+            .collect();
+        if pinned.is_empty() {
+            return html! {};
+        }
+        let shown = if self.pinned_expanded {
+            &pinned[..]
+        } else {
+            &pinned[..1]
+        };
+
+        html! {
+            <div class={format!("px-4 py-2 border-b text-xs {} {}", border_color, bg_tertiary)} role="region" aria-label={t("pinned_messages", self.lang)}>
+                <div class="flex items-center">
+                    <span class={format!("flex-1 font-medium {}", text_primary)}>
+                        {t("pinned_count", self.lang).replace("{count}", &pinned.len().to_string())}
+                    </span>
+                    if pinned.len() > 1 {
+                        <button
+                            type="button"
+                            onclick={ctx.link().callback(|_| Msg::TogglePinnedBanner)}
+                            class={format!("px-2 hover:opacity-75 {}", text_secondary)}
+                            aria-expanded={self.pinned_expanded.to_string()}
+                        >
+                            {t(if self.pinned_expanded { "show_less" } else { "show_all" }, self.lang)}
+                        </button>
+                    }
+                </div>
+                {
+                    shown.iter().map(|&(id, m)| {
+                        let id = id.clone();
+                        let jump = {
+                            let id = id.clone();
+                            ctx.link().callback(move |_| Msg::JumpToMessage(id.clone()))
+                        };
+                        html! {
+                            <div class="flex items-center mt-1">
+                                <button
+                                    type="button"
+                                    onclick={jump}
+                                    class={format!("flex-1 min-w-0 pl-2 text-left truncate border-l-2 border-current hover:opacity-75 {}", text_secondary)}
+                                    title={t("jump_to_message", self.lang)}
+                                >
+                                    <span class="font-medium">{format!("{}: ", m.from)}</span>
+                                    {snippet(&m.message, REPLY_SNIPPET_CHARS)}
+                                </button>
+                                <button
+                                    type="button"
+                                    onclick={ctx.link().callback(move |_| Msg::TogglePin(id.clone()))}
+                                    class={format!("px-2 hover:opacity-75 {}", text_secondary)}
+                                    title={t("unpin_message", self.lang)}
+                                    aria-label={t("unpin_message", self.lang)}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    fn render_new_messages_divider(&self) -> Html {
+        html! {
+            <div class="flex items-center my-4 select-none" role="separator" aria-label={t("new_messages", self.lang)}>
+                <div class="flex-1 border-t border-red-500"></div>
+                <span class="px-3 text-xs font-medium text-red-500">{t("new_messages", self.lang)}</span>
+                <div class="flex-1 border-t border-red-500"></div>
+            </div>
+        }
+    }
+
+    fn render_day_separator(&self, ts: f64) -> Html {
+        let Palette {
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+
+        html! {
+            <div class="flex items-center my-4">
+                <div class={format!("flex-1 border-t {}", border_color)}></div>
+                <span class={format!("px-3 text-xs font-medium {}", text_secondary)}>
+                    {day_label(ts, js_sys::Date::now(), self.lang)}
+                </span>
+                <div class={format!("flex-1 border-t {}", border_color)}></div>
+            </div>
+        }
+    }
+
+    // Placeholder pengganti pesan dari user yang dibisukan
+    fn render_muted_message(&self, ctx: &Context<Self>, m: &MessageData) -> Html {
+        let Palette { text_secondary, .. } = PALETTE;
+        let unmute = {
+            let name = m.from.clone();
+            ctx.link()
+                .callback(move |_| Msg::ToggleMuteUser(name.clone()))
+        };
+
+        html! {
+            <div class={format!("my-1 px-4 text-xs italic select-none {}", text_secondary)}>
+                {t("muted_message_hidden", self.lang).replace("{name}", &m.from)}
+                <button type="button" onclick={unmute} class="ml-2 not-italic underline hover:opacity-75">
+                    {t("unmute", self.lang)}
+                </button>
+            </div>
+        }
+    }
+
+    fn render_system_message(&self, m: &MessageData) -> Html {
+        let Palette { text_secondary, .. } = PALETTE;
+
+        html! {
+            <div class={format!("my-3 text-center text-xs italic {}", text_secondary)}>
+                {highlight(&m.message, self.active_search_query())}
+            </div>
+        }
+    }
+
+    fn render_mention_suggestions(&self, ctx: &Context<Self>) -> Html {
+        if self.mention_suggestions.is_empty() {
+            return html! {};
+        }
+        let Palette {
+            bg_tertiary,
+            text_primary,
+            border_color,
+            ..
+        } = PALETTE;
+
+        html! {
+            <ul class={format!("absolute bottom-full left-0 mb-2 z-20 w-64 py-1 border rounded-lg shadow-lg {} {}", bg_tertiary, border_color)}>
+                {
+                    self.mention_suggestions.iter().enumerate().map(|(i, name)| {
+                        let selected = i == self.mention_selected;
+                        // mousedown agar textarea tidak kehilangan caret sebelum dipilih
+                        let onmousedown = ctx.link().callback(move |e: MouseEvent| {
+                            e.prevent_default();
+                            Msg::CompleteMention(i)
+                        });
+                        html! {
+                            <li
+                                {onmousedown}
+                                class={format!("flex items-center px-3 py-2 text-sm cursor-pointer {}", if selected { "bg-blue-600 text-white" } else { text_primary })}
+                            >
+                                <span
+                                    class="w-6 h-6 mr-2 rounded-full flex items-center justify-center font-bold text-xs"
+                                    style={{
+                                        let color = Self::get_user_color(name);
+                                        format!("background-color: {}; color: {}", color, contrast_text_color(&color))
+                                    }}
+                                >
+                                    {avatar_initial(name)}
+                                </span>
+                                {format!("@{}", name)}
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+        }
+    }
+
+    fn render_emoji_picker(&self, ctx: &Context<Self>) -> Html {
+        if !self.emoji_picker_open {
+            return html! {};
+        }
+        let Palette {
+            bg_tertiary,
+            border_color,
+            hover_bg,
+            ..
+        } = PALETTE;
+
+        html! {
+            <>
+                // Klik di luar popover menutupnya
+                <div class="fixed inset-0 z-10" onclick={ctx.link().callback(|_| Msg::ToggleEmojiPicker)}></div>
+                <div
+                    role="dialog"
+                    aria-label={t("emoji_picker", self.lang)}
+                    class={format!("absolute bottom-full right-0 mb-2 z-20 w-72 p-2 grid grid-cols-8 gap-1 border rounded-lg shadow-lg {} {}", bg_tertiary, border_color)}
+                >
+                    {
+                        EMOJIS.iter().map(|&emoji| {
+                            let onclick = ctx.link().callback(move |_| Msg::InsertEmoji(emoji.to_string()));
+                            html! {
+                                <button type="button" {onclick} class={format!("text-xl rounded {}", hover_bg)} aria-label={t("insert_named", self.lang).replace("{emoji}", emoji)}>
+                                    {emoji}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            </>
+        }
+    }
+
+    fn render_gif_picker(&self, ctx: &Context<Self>) -> Html {
+        if !self.gif_picker_open {
+            return html! {};
+        }
+        let Palette {
+            bg_primary,
+            bg_tertiary,
+            text_primary,
+            text_secondary,
+            border_color,
+            ..
+        } = PALETTE;
+        let status = |text: &str| {
+            html! {
+                <div class={format!("py-8 text-sm text-center {}", text_secondary)}>{text.to_string()}</div>
+            }
+        };
+
+        html! {
+            <>
+                // Klik di luar popover menutupnya
+                <div class="fixed inset-0 z-10" onclick={ctx.link().callback(|_| Msg::ToggleGifPicker)}></div>
+                <div
+                    role="dialog"
+                    aria-label={t("gif_picker", self.lang)}
+                    class={format!("absolute bottom-full right-0 mb-2 z-20 w-80 p-2 border rounded-lg shadow-lg {} {}", bg_tertiary, border_color)}
+                >
+                    <input
+                        type="search"
+                        autofocus=true
+                        placeholder={t("search_gifs_placeholder", self.lang)}
+                        aria-label={t("search_gifs", self.lang)}
+                        value={self.gif_query.clone()}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: HtmlInputElement = e.target_unchecked_into();
+                            Msg::GifQueryChanged(input.value())
+                        })}
+                        class={format!("w-full px-3 py-2 mb-2 text-sm border rounded-lg {} {} {}", bg_primary, text_primary, border_color)}
+                    />
+                    <div class="overflow-y-auto max-h-72" aria-live="polite">
+                        {
+                            match &self.gif_search {
+                                GifSearch::Idle => status(t("gif_search_hint", self.lang)),
+                                GifSearch::Loading => html! {
+                                    <div class="grid grid-cols-3 gap-1">
+                                        {
+                                            (0..6).map(|_| html! {
+                                                <div class="h-20 rounded bg-gray-400 opacity-40 animate-pulse"></div>
+                                            }).collect::<Html>()
+                                        }
+                                    </div>
+                                },
+                                GifSearch::Loaded(gifs) if gifs.is_empty() => status(t("no_gifs", self.lang)),
+                                GifSearch::Loaded(gifs) => html! {
+                                    <div class="grid grid-cols-3 gap-1">
+                                        {
+                                            gifs.iter().map(|gif| {
+                                                let onclick = {
+                                                    let url = gif.url.clone();
+                                                    ctx.link().callback(move |_| Msg::SendGif(url.clone()))
+                                                };
+                                                html! {
+                                                    <button type="button" {onclick} title={gif.description.clone()} class="overflow-hidden rounded hover:ring-2 hover:ring-blue-500">
+                                                        <img
+                                                            src={gif.preview_url.clone()}
+                                                            alt={gif.description.clone()}
+                                                            loading="lazy"
+                                                            class="object-cover w-full h-20"
+                                                        />
+                                                    </button>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </div>
+                                },
+                                GifSearch::Failed(e) => html! {
+                                    <div class="py-8 text-sm text-center text-red-500">{e.clone()}</div>
+                                },
+                            }
+                        }
+                    </div>
+                    <div class={format!("mt-1 text-xs text-right {}", text_secondary)}>{t("powered_by_tenor", self.lang)}</div>
+                </div>
+            </>
+        }
+    }
+}
+
+impl Component for Chat {
+    type Message = Msg;
+    type Properties = ChatProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (user, _) = ctx
+            .link()
+            .context::<User>(Callback::noop())
+            .expect("context to be set");
+        // Register dikirim saat koneksi terbuka (lihat Msg::ConnectionChanged)
+        let wss = WebsocketService::new(
+            DEFAULT_MAX_RETRIES,
+            ctx.link().callback(Msg::ConnectionChanged),
+        );
+        let username = user.username.borrow().clone();
+        if user.color.borrow().is_none() {
+            *user.color.borrow_mut() =
+                storage::load_string(AVATAR_COLOR_KEY).filter(|c| is_hex_color(c));
+        }
+        let document = gloo::utils::document();
+        let visibility_listener = {
+            let link = ctx.link().clone();
+            EventListener::new(&document, "visibilitychange", move |_| {
+                link.send_message(Msg::VisibilityChanged)
+            })
+        };
+        let activity_listeners = {
+            let last_sent = Rc::new(Cell::new(0.0));
+            ["keydown", "mousemove", "mousedown", "touchstart", "wheel"]
+                .into_iter()
+                .map(|event| {
+                    let link = ctx.link().clone();
+                    let last_sent = last_sent.clone();
+                    EventListener::new(&document, event, move |_| {
+                        let now = js_sys::Date::now();
+                        if now - last_sent.get() >= ACTIVITY_DEBOUNCE_MS {
+                            last_sent.set(now);
+                            link.send_message(Msg::Activity);
+                        }
+                    })
+                })
+                .collect()
+        };
+        // Shortcut global, lihat utils::shortcuts untuk daftar tombolnya
+        let keydown_listener = {
+            let link = ctx.link().clone();
+            let options = EventListenerOptions::enable_prevent_default();
+            EventListener::new_with_options(&document, "keydown", options, move |e| {
+                let e = match e.dyn_ref::<KeyboardEvent>() {
+                    Some(e) => e,
+                    None => return,
+                };
+                // Sudah ditangani elemen lain, misal Esc di dropdown mention
+                if e.default_prevented() {
+                    return;
+                }
+                let in_field = e
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                    .is_some_and(|el| matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+                if let Some(shortcut) =
+                    shortcut_for(&e.key(), e.ctrl_key() || e.meta_key(), in_field)
+                {
+                    // Mencegah aksi bawaan browser (bookmark, address bar, ...)
+                    if shortcut != Shortcut::Escape {
+                        e.prevent_default();
+                    }
+                    link.send_message(Msg::Shortcut(shortcut));
+                }
+            })
+        };
+
+        // Link undangan bisa langsung membuka room tertentu
+        let current_room = invite::room_from_location()
+            .filter(|room| ROOMS.contains(&room.as_str()))
+            .unwrap_or_else(|| DEFAULT_ROOM.to_string());
+        let messages = load_session_history(&current_room);
+        Self {
+            users: vec![],
+            user_sort: UserSort::Arrival,
+            user_filter: String::new(),
+            seen_message_ids: message_ids(&messages),
+            messages,
+            chat_input: NodeRef::default(),
+            messages_container: NodeRef::default(),
+            scroll_to_bottom: false,
+            wss,
+            _producer: EventBus::bridge(ctx.link().callback(Msg::HandleMsg)),
+            theme: storage::load_string(THEME_KEY)
+                .and_then(|key| Theme::from_key(&key))
+                .unwrap_or(if load_bool(DARK_MODE_KEY).unwrap_or(false) {
+                    Theme::Dark
+                } else {
+                    Theme::Light
+                }),
+            username,
+            typing_users: HashMap::new(),
+            last_typing_sent: 0.0,
+            typing_ticker: None,
+            clock: Some({
+                let link = ctx.link().clone();
+                Interval::new(CLOCK_TICK_MS, move || link.send_message(Msg::Tick))
+            }),
+            connection: ConnectionState::Connecting,
+            toasts: Vec::new(),
+            drag_depth: 0,
+            connection_dropped: false,
+            reconnect_banner_dismissed: false,
+            id_counter: 0,
+            emoji_picker_open: false,
+            gif_picker_open: false,
+            gif_query: String::new(),
+            gif_search: GifSearch::Idle,
+            gif_search_timeout: None,
+            gif_request: 0,
+            input_len: 0,
+            over_limit_attempt: false,
+            rate_limiter: RateLimiter::new(RATE_LIMIT_MAX, RATE_LIMIT_WINDOW_MS),
+            slow_down: None,
+            sending: None,
+            send_timeout: None,
+            draft_timeout: None,
+            session_history_timeout: None,
+            restore_draft: true,
+            rooms: ROOMS.iter().map(|r| r.to_string()).collect(),
+            current_room,
+            unread_count: 0,
+            base_title: document.title(),
+            _visibility_listener: visibility_listener,
+            notification_permission_requested: false,
+            muted: load_bool(MUTED_KEY).unwrap_or(false),
+            filter_enabled: load_bool(WORD_FILTER_KEY).unwrap_or(false),
+            inline_images: load_bool(INLINE_IMAGES_KEY).unwrap_or(true),
+            optimistic_send: load_bool(OPTIMISTIC_SEND_KEY).unwrap_or(true),
+            batch_inbound: load_bool(BATCH_INBOUND_KEY).unwrap_or(false),
+            inbound_queue: Vec::new(),
+            inbound_frame: None,
+            reaction_alerts: load_bool(REACTION_ALERTS_KEY).unwrap_or(false),
+            recently_offline: Vec::new(),
+            name_confirmed: false,
+            name_retry: None,
+            last_sound_at: 0.0,
+            last_malformed_toast_at: 0.0,
+            notification_sound: None,
+            search_open: false,
+            server_search: ServerSearch::Idle,
+            search_query: String::new(),
+            mention_suggestions: vec![],
+            mention_selected: 0,
+            mention_start: 0,
+            mention_dismissed_at: None,
+            users_loaded: false,
+            user,
+            failed_images: HashSet::new(),
+            expanded_messages: HashSet::new(),
+            replying_to: None,
+            just_added: HashSet::new(),
+            enter_animation_timeout: None,
+            reduced_motion: prefers_reduced_motion(),
+            context_menu: None,
+            pinned_messages: Vec::new(),
+            pinned_expanded: false,
+            editing: None,
+            jump_target: None,
+            highlighted: None,
+            highlight_timeout: None,
+            link_previews: HashMap::new(),
+            read_receipts: HashMap::new(),
+            last_read_sent: None,
+            copied: None,
+            invite_copied: false,
+            scroll_top: 0.0,
+            viewport_height: web_sys::window()
+                .and_then(|w| w.inner_height().ok())
+                .and_then(|h| h.as_f64())
+                .unwrap_or(800.0),
+            pinned_to_bottom: true,
+            unseen_count: 0,
+            unread_marker: None,
+            load_timeout: Some(Self::load_timeout(ctx)),
+            load_timed_out: false,
+            has_more_history: true,
+            history_loading: false,
+            history_scroll_anchor: None,
+            settings_open: false,
+            sidebar_open: false,
+            moderation_menu: None,
+            confirm_moderation: None,
+            removed: None,
+            muted_users: storage::load_string(MUTED_USERS_KEY)
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            font_size: storage::load_string(FONT_SIZE_KEY)
+                .and_then(|key| FontSize::from_key(&key))
+                .unwrap_or(FontSize::Medium),
+            lang: storage::load_string(LANG_KEY)
+                .and_then(|key| Lang::from_key(&key))
+                .unwrap_or_else(Lang::from_browser),
+            density: storage::load_string(DENSITY_KEY)
+                .and_then(|key| Density::from_key(&key))
+                .unwrap_or(Density::Comfortable),
+            export_format: ExportFormat::Json,
+            import_mode: ImportMode::Merge,
+            import_error: None,
+            confirm_clear: None,
+            cleared_backup: vec![],
+            undo_timeout: None,
+            _keydown_listener: keydown_listener,
+            search_input: NodeRef::default(),
+            focus_search: false,
+            focus_input: false,
+            _activity_listeners: activity_listeners,
+            last_activity: js_sys::Date::now(),
+            presence: Presence::Online,
+            statuses: HashMap::new(),
+            conversations: HashMap::new(),
+            active_dm: None,
+            dm_unread: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::HandleMsg(s) => {
+                // Frame yang tidak dikenali diabaikan agar chat tetap berjalan
+                let msg = match parse_frame(&s) {
+                    Ok(Some(msg)) => msg,
+                    Ok(None) => {
+                        log::debug!("ignoring frame with unknown message type: {}", s);
+                        return false;
+                    }
+                    Err(e) => {
+                        log::warn!("ignoring malformed frame {:?}: {}", s, e);
+                        return self.warn_malformed_frame();
+                    }
+                };
+                // Frame lain ikut antre selama antrean belum kosong agar urutannya terjaga
+                let is_message = matches!(
+                    msg.message_type,
+                    MsgTypes::Message | MsgTypes::PrivateMessage
+                );
+                if (self.batch_inbound && is_message) || !self.inbound_queue.is_empty() {
+                    self.inbound_queue.push(msg);
+                    if self.inbound_frame.is_none() {
+                        let link = ctx.link().clone();
+                        self.inbound_frame = Some(request_animation_frame(move |_| {
+                            link.send_message(Msg::FlushInbound)
+                        }));
+                    }
+                    return false;
+                }
+                self.handle_frame(ctx, msg)
+            }
+            Msg::FlushInbound => {
+                self.inbound_frame = None;
+                let mut changed = false;
+                for msg in std::mem::take(&mut self.inbound_queue) {
+                    changed |= self.handle_frame(ctx, msg);
+                }
+                changed
+            }
+            Msg::SubmitMessage => {
+                // Jangan kirim saat terputus, biarkan teks tetap di input
+                if self.connection == ConnectionState::Disconnected {
+                    self.show_toast(Toast::warning(t("toast_offline", self.lang)));
+                    return true;
+                }
+                // Enter berulang selama pesan sebelumnya belum dikonfirmasi diabaikan
+                if self.sending.is_some() {
+                    return false;
+                }
+                let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+                    Some(input) => input,
+                    None => return false,
+                };
+                let value = input.value().trim().to_string();
+                if value.chars().count() > MAX_MESSAGE_LEN {
+                    self.over_limit_attempt = true;
+                    return true;
+                }
+                if value.is_empty() {
+                    return false;
+                }
+                if let Some(id) = self.editing.clone() {
+                    let value = expand_shortcodes(&value);
+                    let original = self
+                        .current_messages()
+                        .iter()
+                        .find(|m| m.id.as_ref() == Some(&id))
+                        .map(|m| m.message.clone());
+                    match original {
+                        // Pesan dihapus selagi diedit
+                        None => {
+                            self.push_system_message(
+                                t("edited_message_deleted", self.lang).to_string(),
+                            );
+                        }
+                        Some(original) if original == value => {}
+                        // Perubahan baru terlihat setelah server menggemakan EditMessage
+                        Some(_) => {
+                            let edit = MessageEdit {
+                                id,
+                                from: self.username.clone(),
+                                message: value,
+                            };
+                            if !self.send(&WebSocketMessage::new(
+                                MsgTypes::EditMessage,
+                                serde_json::to_string(&edit).ok(),
+                            )) {
+                                return false;
+                            }
+                        }
+                    }
+                    self.reset_input(&input);
+                    self.focus_input = true;
+                    return true;
+                }
+                let text = match parse_command(&value) {
+                    // Shortcode diganti sebelum kirim agar penerima melihat emojinya
+                    Command::Say(text) => expand_shortcodes(text),
+                    Command::Shrug(text) => shrug(&expand_shortcodes(text)),
+                    Command::Me(action) if !action.is_empty() => {
+                        format!("/me {}", expand_shortcodes(action))
+                    }
+                    Command::Me(_) => return false,
+                    Command::Clear => {
+                        self.reset_input(&input);
+                        self.focus_input = true;
+                        ctx.link().send_message(Msg::ClearChat);
+                        return true;
+                    }
+                    Command::Unknown(name) => {
+                        let notice = t("unknown_command", self.lang).replace("{name}", name);
+                        self.reset_input(&input);
+                        self.focus_input = true;
+                        self.push_system_message(notice);
+                        self.scroll_to_bottom = true;
+                        return true;
+                    }
+                };
+
+                // Teks tetap di input agar bisa dikirim ulang setelah jeda
+                if self.send_chat_message(ctx, text) {
+                    self.reset_input(&input);
+                }
+                self.focus_input = true;
+                true
+            }
+            Msg::SetTheme(theme) => {
+                storage::save_string(THEME_KEY, theme.key());
+                self.theme = theme;
+                true
+            }
+            Msg::CaretMoved => {
+                let had_suggestions = !self.mention_suggestions.is_empty();
+                self.sync_mention_suggestions();
+                had_suggestions || !self.mention_suggestions.is_empty()
+            }
+            Msg::MentionNavigate(step) => {
+                let len = self.mention_suggestions.len() as isize;
+                if len > 0 {
+                    self.mention_selected =
+                        (self.mention_selected as isize + step).rem_euclid(len) as usize;
+                }
+                true
+            }
+            Msg::CompleteMention(index) => {
+                let name = match self.mention_suggestions.get(index) {
+                    Some(name) => format!("@{} ", name),
+                    None => return false,
+                };
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let end = input
+                        .selection_start()
+                        .ok()
+                        .flatten()
+                        .unwrap_or(self.mention_start);
+                    if let Err(e) =
+                        input.set_range_text_with_start_and_end(&name, self.mention_start, end)
+                    {
+                        log::debug!("error completing mention: {:?}", e);
+                    }
+                    let caret = self.mention_start + name.encode_utf16().count() as u32;
+                    let _ = input.set_selection_range(caret, caret);
+                    let _ = input.focus();
+                }
+                self.mention_suggestions.clear();
+                self.sync_input_len();
+                true
+            }
+            Msg::DismissMention => {
+                self.mention_dismissed_at = Some(self.mention_start);
+                self.mention_suggestions.clear();
+                true
+            }
+            Msg::Shortcut(Shortcut::FocusSearch) => {
+                self.search_open = true;
+                self.focus_search = true;
+                true
+            }
+            Msg::Shortcut(Shortcut::CycleTheme) => {
+                self.update(ctx, Msg::SetTheme(self.theme.next()))
+            }
+            Msg::Shortcut(Shortcut::ClearChat) => self.update(ctx, Msg::RequestClear),
+            Msg::Shortcut(Shortcut::Escape) => self.close_top_layer(),
+            Msg::ToggleSearch => {
+                self.search_open = !self.search_open;
+                self.focus_search = self.search_open;
+                if !self.search_open {
+                    self.search_query.clear();
+                    self.server_search = ServerSearch::Idle;
+                    self.scroll_to_bottom = true;
+                }
+                true
+            }
+            Msg::SearchChanged(query) => {
+                if query.trim().is_empty() && !self.search_query.trim().is_empty() {
+                    self.scroll_to_bottom = true;
+                }
+                self.search_query = query;
+                // Hasil server untuk query lama tidak lagi relevan
+                self.server_search = ServerSearch::Idle;
+                true
+            }
+            Msg::SearchAll => {
+                let query = self.search_query.trim().to_string();
+                if query.is_empty() {
+                    return false;
+                }
+                let request = SearchRequest {
+                    query: query.clone(),
+                };
+                let sent = self.send(&WebSocketMessage {
+                    room: self.active_dm.is_none().then(|| self.current_room.clone()),
+                    to: self.active_dm.clone(),
+                    ..WebSocketMessage::new(MsgTypes::Search, serde_json::to_string(&request).ok())
+                });
+                if sent {
+                    self.server_search = ServerSearch::Loading(query);
+                }
+                sent
+            }
+            Msg::OpenSearchResult(id) => {
+                // Tutup pencarian dulu agar filter lokal tidak menyembunyikan pesan tujuan
+                self.search_open = false;
+                self.search_query.clear();
+                self.server_search = ServerSearch::Idle;
+                self.update(ctx, Msg::JumpToMessage(id));
+                true
+            }
+            Msg::ToggleMute => {
+                self.muted = !self.muted;
+                save_bool(MUTED_KEY, self.muted);
+                true
+            }
+            Msg::SaveDraft => {
+                self.save_draft();
+                false
+            }
+            Msg::DraftRestored => true,
+            Msg::ReplyTo(id) => {
+                self.replying_to = id;
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let _ = input.focus();
+                }
+                true
+            }
+            Msg::JumpToMessage(id) => {
+                let index = match self
+                    .visible_messages()
+                    .iter()
+                    .position(|m| m.id.as_ref() == Some(&id))
+                {
+                    Some(index) => index,
+                    None => return false,
+                };
+                // Geser jendela render dulu agar elemen tujuan ada di DOM
+                self.pinned_to_bottom = false;
+                self.scroll_top = index as f64 * ESTIMATED_ROW_HEIGHT;
+                if let Some(el) = self.messages_container.cast::<web_sys::Element>() {
+                    el.set_scroll_top(self.scroll_top as i32);
+                }
+                self.jump_target = Some(id.clone());
+                self.highlighted = Some(id);
+                let link = ctx.link().clone();
+                self.highlight_timeout = Some(Timeout::new(JUMP_HIGHLIGHT_MS, move || {
+                    link.send_message(Msg::ClearHighlight)
+                }));
+                true
+            }
+            Msg::TogglePin(id) => {
+                // Diterapkan langsung seperti reaksi; gema dari server tidak mengubah apa-apa
+                let message_type = if self.pinned_messages.contains(&id) {
+                    self.pinned_messages.retain(|pinned| *pinned != id);
+                    MsgTypes::Unpin
+                } else {
+                    self.pinned_messages.push(id.clone());
+                    MsgTypes::Pin
+                };
+                self.send(&WebSocketMessage::new(message_type, Some(id)));
+                true
+            }
+            Msg::OpenContextMenu(id, x, y) => {
+                self.context_menu = Some((id, x, y));
+                true
+            }
+            Msg::CloseContextMenu => self.context_menu.take().is_some(),
+            Msg::ContextAction(action) => {
+                let id = match self.context_menu.take() {
+                    Some((id, _, _)) => id,
+                    None => return false,
+                };
+                let msg = match action {
+                    ContextAction::Reply => Msg::ReplyTo(Some(id)),
+                    ContextAction::Copy => {
+                        match self
+                            .current_messages()
+                            .iter()
+                            .find(|m| m.id.as_ref() == Some(&id))
+                        {
+                            Some(m) => Msg::CopyMessage(m.message.clone()),
+                            None => return true,
+                        }
+                    }
+                    ContextAction::Pin => Msg::TogglePin(id),
+                    ContextAction::Delete => Msg::DeleteMessage(id),
+                    ContextAction::React(emoji) => Msg::ToggleReaction {
+                        id,
+                        emoji: emoji.to_string(),
+                    },
+                };
+                self.update(ctx, msg);
+                true
+            }
+            Msg::TogglePinnedBanner => {
+                self.pinned_expanded = !self.pinned_expanded;
+                true
+            }
+            Msg::EditLast => {
+                // Pesan yang sudah dihapus tidak ada lagi di daftar, jadi yang
+                // terakhir tersisa yang dipakai
+                let last = self.current_messages().iter().rev().find(|m| {
+                    m.from == self.username
+                        && m.kind == MessageKind::Chat
+                        && m.id.is_some()
+                        && m.status != Some(DeliveryStatus::Failed)
+                });
+                let (id, text) = match last {
+                    Some(m) => (m.id.clone(), m.message.clone()),
+                    None => return false,
+                };
+                let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+                    Some(input) => input,
+                    None => return false,
+                };
+                input.set_value(&text);
+                fit_to_content(&input);
+                let len = text.encode_utf16().count() as u32;
+                let _ = input.set_selection_range(len, len);
+                self.sync_input_len();
+                self.replying_to = None;
+                self.editing = id;
+                true
+            }
+            Msg::CancelEdit => match self.chat_input.cast::<HtmlTextAreaElement>() {
+                Some(input) => {
+                    self.reset_input(&input);
+                    true
+                }
+                None => false,
+            },
+            Msg::ClearInput => match self.chat_input.cast::<HtmlTextAreaElement>() {
+                Some(input) => {
+                    self.reset_input(&input);
+                    let _ = input.focus();
+                    true
+                }
+                None => false,
+            },
+            Msg::ClearHighlight => {
+                self.highlight_timeout = None;
+                self.highlighted.take().is_some()
+            }
+            Msg::EnterAnimationsDone(ids) => {
+                self.enter_animation_timeout = None;
+                for id in &ids {
+                    self.just_added.remove(id);
+                }
+                // Tidak perlu render: kelas animasi yang tertinggal tidak terlihat
+                false
+            }
+            Msg::ToggleExpanded(id) => {
+                if !self.expanded_messages.remove(&id) {
+                    self.expanded_messages.insert(id);
+                }
+                true
+            }
+            Msg::SaveSessionHistory => {
+                self.session_history_timeout = None;
+                save_session_history(&self.current_room, &self.messages);
+                false
+            }
+            Msg::SetUserSort(sort) => {
+                self.user_sort = sort;
+                true
+            }
+            Msg::UserFilterChanged(filter) => {
+                self.user_filter = filter;
+                true
+            }
+            Msg::SlowDownExpired => self.slow_down.take().is_some(),
+            Msg::SendTimedOut => {
+                self.send_timeout = None;
+                self.sending.take().is_some()
+            }
+            Msg::ToggleWordFilter => {
+                self.filter_enabled = !self.filter_enabled;
+                save_bool(WORD_FILTER_KEY, self.filter_enabled);
+                true
+            }
+            Msg::ToggleInlineImages => {
+                self.inline_images = !self.inline_images;
+                save_bool(INLINE_IMAGES_KEY, self.inline_images);
+                true
+            }
+            Msg::ToggleOptimisticSend => {
+                self.optimistic_send = !self.optimistic_send;
+                save_bool(OPTIMISTIC_SEND_KEY, self.optimistic_send);
+                true
+            }
+            Msg::ToggleReactionAlerts => {
+                self.reaction_alerts = !self.reaction_alerts;
+                save_bool(REACTION_ALERTS_KEY, self.reaction_alerts);
+                true
+            }
+            Msg::ToggleBatchInbound => {
+                self.batch_inbound = !self.batch_inbound;
+                save_bool(BATCH_INBOUND_KEY, self.batch_inbound);
+                true
+            }
+            Msg::ToggleSettings => {
+                self.settings_open = !self.settings_open;
+                true
+            }
+            Msg::ToggleSidebar => {
+                self.sidebar_open = !self.sidebar_open;
+                true
+            }
+            Msg::ToggleModerationMenu(name) => {
+                if self.moderation_menu.as_ref() == Some(&name) {
+                    self.moderation_menu = None;
+                } else {
+                    self.moderation_menu = Some(name);
+                }
+                true
+            }
+            Msg::RequestModeration(action, name) => {
+                self.moderation_menu = None;
+                self.confirm_moderation = Some((action, name));
+                true
+            }
+            Msg::ConfirmModeration => {
+                let (action, name) = match self.confirm_moderation.take() {
+                    Some(pending) => pending,
+                    None => return false,
+                };
+                self.send(&WebSocketMessage {
+                    room: Some(self.current_room.clone()),
+                    ..WebSocketMessage::new(action.msg_type(), Some(name))
+                });
+                true
+            }
+            Msg::CancelModeration => self.confirm_moderation.take().is_some(),
+            Msg::ToggleMuteUser(name) => {
+                if name == self.username {
+                    return false;
+                }
+                if !self.muted_users.remove(&name) {
+                    self.muted_users.insert(name);
+                }
+                match serde_json::to_string(&self.muted_users) {
+                    Ok(json) => storage::save_string(MUTED_USERS_KEY, &json),
+                    Err(e) => log::debug!("error saving muted users: {:?}", e),
+                }
+                true
+            }
+            Msg::SetFontSize(font_size) => {
+                storage::save_string(FONT_SIZE_KEY, font_size.key());
+                self.font_size = font_size;
+                true
+            }
+            Msg::SetLang(lang) => {
+                storage::save_string(LANG_KEY, lang.key());
+                self.lang = lang;
+                true
+            }
+            Msg::SetDensity(density) => {
+                storage::save_string(DENSITY_KEY, density.key());
+                self.density = density;
+                true
+            }
+            Msg::SetExportFormat(fmt) => {
+                self.export_format = fmt;
+                true
+            }
+            Msg::ExportChat => {
+                let contents = export_messages(self.current_messages(), self.export_format);
+                let name = match &self.active_dm {
+                    Some(peer) => format!("dm-{}", peer),
+                    None => self.current_room.clone(),
+                };
+                let filename = format!(
+                    "yewchat-{}-{}.{}",
+                    name,
+                    format_date(js_sys::Date::now()),
+                    self.export_format.key()
+                );
+                if let Err(e) =
+                    download::save_file(&filename, self.export_format.mime_type(), &contents)
+                {
+                    log::error!("error exporting chat: {:?}", e);
+                    self.show_toast(Toast::error(t("toast_export_failed", self.lang)));
+                    return true;
+                }
+                false
+            }
+            Msg::SetImportMode(mode) => {
+                self.import_mode = mode;
+                true
+            }
+            Msg::ImportFile(file) => {
+                let (link, lang) = (ctx.link().clone(), self.lang);
+                spawn_local(async move {
+                    let result = gloo::file::futures::read_as_text(&file.into())
+                        .await
+                        .map_err(|e| {
+                            t("import_read_failed", lang).replace("{error}", &e.to_string())
+                        });
+                    link.send_message(Msg::Imported(result));
+                });
+                false
+            }
+            Msg::DragEnter => {
+                self.drag_depth += 1;
+                self.drag_depth == 1
+            }
+            Msg::DragLeave => {
+                self.drag_depth = self.drag_depth.saturating_sub(1);
+                self.drag_depth == 0
+            }
+            Msg::DropFile(file) => {
+                self.drag_depth = 0;
+                let file = match file {
+                    Some(file) => file,
+                    None => return true,
+                };
+                if !IMAGE_MIME_TYPES.contains(&file.type_().as_str()) {
+                    self.show_toast(Toast::error(t("toast_drop_not_image", self.lang)));
+                    return true;
+                }
+                if file.size() > MAX_DROP_IMAGE_BYTES {
+                    self.show_toast(Toast::error(t("toast_drop_too_large", self.lang)));
+                    return true;
+                }
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let result = gloo::file::futures::read_as_data_url(&file.into())
+                        .await
+                        .map_err(|e| e.to_string());
+                    link.send_message(Msg::DroppedImageRead(result));
+                });
+                true
+            }
+            Msg::DroppedImageRead(result) => {
+                let data_url = match result {
+                    Ok(data_url) => data_url,
+                    Err(e) => {
+                        log::warn!("error reading dropped file: {}", e);
+                        self.show_toast(Toast::error(t("toast_drop_read_failed", self.lang)));
                         return true;
                     }
-                    MsgTypes::Message => {
-                        let message_data: MessageData =
-                            serde_json::from_str(&msg.data.unwrap()).unwrap();
-                        self.messages.push(message_data);
+                };
+                if self.connection == ConnectionState::Disconnected {
+                    self.show_toast(Toast::warning(t("toast_offline", self.lang)));
+                    return true;
+                }
+                self.send_chat_message(ctx, data_url)
+            }
+            Msg::Imported(result) => {
+                let imported = match result.and_then(|json| parse_export(&json, self.lang)) {
+                    Ok(imported) => imported,
+                    Err(e) => {
+                        self.import_error = Some(e);
                         return true;
                     }
-                    _ => {
-                        return false;
+                };
+                self.import_error = None;
+                self.discard_undo();
+                let mode = self.import_mode;
+                let messages = self.current_messages_mut();
+                if mode == ImportMode::Replace {
+                    messages.clear();
+                }
+                let added = merge_messages(messages, imported);
+                trim_oldest(messages);
+                self.push_system_message(
+                    t("imported_count", self.lang).replace("{count}", &added.to_string()),
+                );
+                self.scroll_to_bottom = true;
+                true
+            }
+            Msg::RequestClear => {
+                if self.confirm_clear.take().is_some() {
+                    ctx.link().send_message(Msg::ClearChat);
+                    return true;
+                }
+                let link = ctx.link().clone();
+                self.confirm_clear = Some(Timeout::new(CLEAR_CONFIRM_MS, move || {
+                    link.send_message(Msg::CancelClear)
+                }));
+                true
+            }
+            Msg::CancelClear => self.confirm_clear.take().is_some(),
+            Msg::ClearChat => {
+                if self.current_messages().is_empty() {
+                    return false;
+                }
+                self.cleared_backup = std::mem::take(self.current_messages_mut());
+                if self.active_dm.is_none() {
+                    storage::session_remove(&session_history_key(&self.current_room));
+                }
+                ctx.link().send_message(Msg::ShowUndo(true));
+                true
+            }
+            Msg::ShowUndo(true) => {
+                let link = ctx.link().clone();
+                self.undo_timeout = Some(Timeout::new(UNDO_CLEAR_MS, move || {
+                    link.send_message(Msg::ShowUndo(false))
+                }));
+                true
+            }
+            Msg::ShowUndo(false) => self.discard_undo(),
+            Msg::UndoClear => {
+                if self.cleared_backup.is_empty() {
+                    return false;
+                }
+                *self.current_messages_mut() = std::mem::take(&mut self.cleared_backup);
+                self.undo_timeout = None;
+                self.scroll_to_bottom = true;
+                true
+            }
+            Msg::InputChanged => {
+                self.schedule_draft_save(ctx);
+                self.sync_input_len();
+                self.sync_mention_suggestions();
+                self.over_limit_attempt = false;
+                let now = js_sys::Date::now();
+                if now - self.last_typing_sent >= TYPING_SEND_INTERVAL_MS {
+                    self.last_typing_sent = now;
+                    self.send(&WebSocketMessage::new(
+                        MsgTypes::Typing,
+                        Some(self.username.clone()),
+                    ));
+                }
+                true
+            }
+            Msg::PruneTyping => {
+                let now = js_sys::Date::now();
+                let before = self.typing_users.len();
+                self.typing_users
+                    .retain(|_, last_seen| now - *last_seen < TYPING_EXPIRY_MS);
+                if self.typing_users.is_empty() {
+                    self.typing_ticker = None;
+                }
+                self.typing_users.len() != before
+            }
+            Msg::ConnectionChanged(state) => {
+                log::debug!("connection state: {:?}", state);
+                let changed = self.connection != state;
+                if self.connection == ConnectionState::Connected
+                    && state != ConnectionState::Connected
+                {
+                    ctx.props().on_disconnected.emit(());
+                }
+                if self.connection == ConnectionState::Connected
+                    && state == ConnectionState::Connecting
+                {
+                    self.connection_dropped = true;
+                    self.show_toast(Toast::warning(t("toast_connection_lost", self.lang)));
+                }
+                self.connection = state;
+                if state != ConnectionState::Connected {
+                    // Gema dari koneksi yang putus tidak akan datang
+                    self.sending = None;
+                    self.send_timeout = None;
+                }
+                if state == ConnectionState::Disconnected {
+                    self.reconnect_banner_dismissed = false;
+                    self.show_toast(Toast::error(t("toast_reconnect_failed", self.lang)));
+                    let pending = self
+                        .messages
+                        .iter_mut()
+                        .chain(self.conversations.values_mut().flatten())
+                        .filter(|m| m.status == Some(DeliveryStatus::Sending));
+                    for m in pending {
+                        m.status = Some(DeliveryStatus::Failed);
+                    }
+                }
+                if state == ConnectionState::Connected {
+                    ctx.props().on_connected.emit(());
+                    if std::mem::take(&mut self.connection_dropped) {
+                        self.show_toast(Toast::info(t("toast_reconnected", self.lang)));
+                    }
+                    self.users_loaded = false;
+                    // Daftar ulang setiap (re)connect agar server menambahkan user lagi
+                    self.register();
+                    self.show_self_pending();
+                    if self.presence == Presence::Away {
+                        self.send_presence();
+                    }
+                    // Permintaan yang terputus di tengah jalan tidak akan dibalas
+                    self.history_loading = false;
+                    if self.messages.is_empty() {
+                        self.request_history();
+                    }
+                }
+                changed
+            }
+            Msg::ShowToast(toast) => {
+                self.show_toast(toast);
+                true
+            }
+            Msg::DismissToast(id) => {
+                let before = self.toasts.len();
+                self.toasts.retain(|toast| toast.id != id);
+                self.toasts.len() != before
+            }
+            Msg::ReconnectNow => {
+                self.wss.reconnect_now();
+                false
+            }
+            Msg::DismissReconnectBanner => {
+                self.reconnect_banner_dismissed = true;
+                true
+            }
+            Msg::ToggleEmojiPicker => {
+                self.emoji_picker_open = !self.emoji_picker_open;
+                true
+            }
+            Msg::ToggleGifPicker => {
+                self.gif_picker_open = !self.gif_picker_open;
+                true
+            }
+            Msg::GifQueryChanged(query) => {
+                self.gif_query = query;
+                let link = ctx.link().clone();
+                self.gif_search_timeout = Some(Timeout::new(GIF_SEARCH_DEBOUNCE_MS, move || {
+                    link.send_message(Msg::SearchGifs)
+                }));
+                true
+            }
+            Msg::SearchGifs => {
+                self.gif_search_timeout = None;
+                self.gif_request += 1;
+                let query = self.gif_query.trim().to_string();
+                if query.is_empty() {
+                    self.gif_search = GifSearch::Idle;
+                    return true;
+                }
+                self.gif_search = GifSearch::Loading;
+                let (link, request) = (ctx.link().clone(), self.gif_request);
+                spawn_local(async move {
+                    let result = gif::search_gifs(&query).await;
+                    link.send_message(Msg::GifsLoaded(request, result));
+                });
+                true
+            }
+            Msg::GifsLoaded(request, result) => {
+                if request != self.gif_request {
+                    return false;
+                }
+                self.gif_search = match result {
+                    Ok(gifs) => GifSearch::Loaded(gifs),
+                    Err(e) => GifSearch::Failed(e),
+                };
+                true
+            }
+            Msg::SendGif(url) => {
+                if self.connection == ConnectionState::Disconnected {
+                    return false;
+                }
+                if self.send_chat_message(ctx, url) {
+                    self.gif_picker_open = false;
+                    self.focus_input = true;
+                }
+                true
+            }
+            Msg::InsertEmoji(emoji) => {
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    // Offset selection dalam UTF-16, jadi biarkan browser yang mengganti teks
+                    let len = input.value().encode_utf16().count() as u32;
+                    let start = input.selection_start().ok().flatten().unwrap_or(len);
+                    let end = input.selection_end().ok().flatten().unwrap_or(start);
+                    if let Err(e) = input.set_range_text_with_start_and_end(&emoji, start, end) {
+                        log::debug!("error inserting emoji: {:?}", e);
+                    }
+                    let caret = start + emoji.encode_utf16().count() as u32;
+                    let _ = input.set_selection_range(caret, caret);
+                    fit_to_content(&input);
+                    let _ = input.focus();
+                }
+                self.sync_input_len();
+                self.schedule_draft_save(ctx);
+                self.emoji_picker_open = false;
+                true
+            }
+            Msg::SwitchRoom(room) => {
+                // Memilih room menutup drawer di layar sempit
+                let closed_sidebar = std::mem::take(&mut self.sidebar_open);
+                if room == self.current_room && self.active_dm.is_none() {
+                    return closed_sidebar;
+                }
+                self.save_draft();
+                self.restore_draft = true;
+                if room == self.current_room {
+                    // Klik room yang aktif saat DM terbuka kembali ke room
+                    self.active_dm = None;
+                    self.replying_to = None;
+                    self.unread_marker = None;
+                    self.editing = None;
+                    self.discard_undo();
+                    self.scroll_to_bottom = true;
+                    return true;
+                }
+                self.active_dm = None;
+                self.replying_to = None;
+                self.unread_marker = None;
+                self.editing = None;
+                // Pesan dan daftar user hanya berlaku untuk room yang aktif
+                save_session_history(&self.current_room, &self.messages);
+                self.messages = load_session_history(&room);
+                self.seen_message_ids = message_ids(&self.messages);
+                self.discard_undo();
+                self.users.clear();
+                self.recently_offline.clear();
+                self.users_loaded = false;
+                self.load_timeout = Some(Self::load_timeout(ctx));
+                self.load_timed_out = false;
+                self.typing_users.clear();
+                self.pinned_to_bottom = true;
+                self.unseen_count = 0;
+                self.send(&WebSocketMessage::new(
+                    MsgTypes::JoinRoom,
+                    Some(room.clone()),
+                ));
+                self.current_room = room;
+                self.show_self_pending();
+                self.read_receipts.clear();
+                self.last_read_sent = None;
+                self.has_more_history = true;
+                self.history_loading = false;
+                self.history_scroll_anchor = None;
+                self.request_history();
+                true
+            }
+            // Hanya render ulang agar waktu relatif tetap akurat
+            Msg::Tick => {
+                let now = js_sys::Date::now();
+                if self.presence == Presence::Online && now - self.last_activity >= AWAY_AFTER_MS {
+                    self.set_presence(Presence::Away);
+                }
+                self.recently_offline
+                    .retain(|(_, seen)| now - seen < RECENTLY_OFFLINE_MS);
+                // Waktu relatif ("5m ago") perlu diperbarui
+                !self.current_messages().is_empty() || !self.recently_offline.is_empty()
+            }
+            Msg::Activity => {
+                self.last_activity = js_sys::Date::now();
+                if self.presence == Presence::Away {
+                    self.set_presence(Presence::Online);
+                }
+                self.mark_read();
+                false
+            }
+            Msg::OpenDm(name) => {
+                let closed_sidebar = std::mem::take(&mut self.sidebar_open);
+                if name == self.username || self.active_dm.as_ref() == Some(&name) {
+                    return closed_sidebar;
+                }
+                self.save_draft();
+                self.restore_draft = true;
+                self.discard_undo();
+                self.dm_unread.remove(&name);
+                self.active_dm = Some(name);
+                self.replying_to = None;
+                self.unread_marker = None;
+                self.editing = None;
+                self.pinned_to_bottom = true;
+                self.unseen_count = 0;
+                self.scroll_to_bottom = true;
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let _ = input.focus();
+                }
+                true
+            }
+            Msg::SetAvatarColor(color) => {
+                let color = color.filter(|c| is_hex_color(c));
+                match &color {
+                    Some(c) => storage::save_string(AVATAR_COLOR_KEY, c),
+                    None => storage::remove(AVATAR_COLOR_KEY),
+                }
+                let own_color = color
+                    .clone()
+                    .unwrap_or_else(|| Self::get_user_color(&self.username));
+                if let Some(me) = self.users.iter_mut().find(|u| u.name == self.username) {
+                    me.color = own_color;
+                }
+                *self.user.color.borrow_mut() = color;
+                // Register ulang agar server menyebarkan profil baru
+                self.register();
+                true
+            }
+            Msg::ImageFailed(url) => self.failed_images.insert(url),
+            Msg::CopyMessage(text) => {
+                let link = ctx.link().clone();
+                let failed = t("toast_copy_failed", self.lang);
+                spawn_local(async move {
+                    if let Err(e) = clipboard::write_text(&text).await {
+                        log::debug!("error copying message: {:?}", e);
+                        link.send_message(Msg::ShowToast(Toast::error(failed)));
+                        return;
+                    }
+                    link.send_message(Msg::Copied(Some(text)));
+                    TimeoutFuture::new(COPIED_FEEDBACK_MS).await;
+                    link.send_message(Msg::Copied(None));
+                });
+                false
+            }
+            Msg::Scrolled(scroll_top) => {
+                let count = self.visible_messages().len();
+                let before = self.message_window(count);
+                self.scroll_top = scroll_top;
+                if let Some(el) = self.messages_container.cast::<web_sys::Element>() {
+                    self.viewport_height = el.client_height() as f64;
+                }
+                let was_pinned = self.pinned_to_bottom;
+                self.pinned_to_bottom = self.is_near_bottom();
+                let had_unseen = self.unseen_count > 0;
+                let mut cleared_marker = false;
+                if self.pinned_to_bottom {
+                    self.unseen_count = 0;
+                    self.mark_read();
+                    // Scroll otomatis saat tab tersembunyi tidak dihitung menyusul
+                    if !gloo::utils::document().hidden() {
+                        cleared_marker = self.unread_marker.take().is_some();
                     }
                 }
+                if scroll_top < HISTORY_LOAD_THRESHOLD as f64
+                    && self.active_dm.is_none()
+                    && !self.messages.is_empty()
+                {
+                    self.request_history();
+                }
+                self.message_window(count) != before
+                    || self.pinned_to_bottom != was_pinned
+                    || (had_unseen && self.unseen_count == 0)
+                    || cleared_marker
             }
-            Msg::SubmitMessage => {
-                let input = self.chat_input.cast::<HtmlInputElement>();
-                if let Some(input) = input {
-                    let value = input.value().trim().to_string();
-                    if !value.is_empty() {
-                        let message = WebSocketMessage {
-                            message_type: MsgTypes::Message,
-                            data: Some(value),
-                            data_array: None,
-                        };
-                        if let Err(e) = self
-                            .wss
-                            .tx
-                            .clone()
-                            .try_send(serde_json::to_string(&message).unwrap())
-                        {
-                            log::debug!("error sending to channel: {:?}", e);
-                        }
-                        input.set_value("");
+            Msg::JumpToBottom => {
+                self.unseen_count = 0;
+                self.pinned_to_bottom = true;
+                self.scroll_to_bottom = true;
+                true
+            }
+            Msg::LoadTimedOut => {
+                self.load_timeout = None;
+                self.load_timed_out = !self.users_loaded;
+                self.load_timed_out
+            }
+            Msg::Copied(text) => {
+                self.copied = text;
+                true
+            }
+            Msg::CopyInviteLink => {
+                let link = ctx.link().clone();
+                let failed = t("toast_copy_failed", self.lang);
+                let url = invite::invite_link(&self.current_room);
+                spawn_local(async move {
+                    let result = match url {
+                        Some(url) => clipboard::write_text(&url).await,
+                        None => Err(JsValue::from_str("invite link unavailable")),
+                    };
+                    if let Err(e) = result {
+                        log::debug!("error copying invite link: {:?}", e);
+                        link.send_message(Msg::ShowToast(Toast::error(failed)));
+                        return;
                     }
-                };
+                    link.send_message(Msg::InviteCopied(true));
+                    TimeoutFuture::new(COPIED_FEEDBACK_MS).await;
+                    link.send_message(Msg::InviteCopied(false));
+                });
+                false
+            }
+            Msg::RetryRegister => {
+                self.name_retry = None;
+                if self.connection == ConnectionState::Connected {
+                    self.register();
+                }
+                false
+            }
+            Msg::SignOut => {
+                // Tanpa ini halaman login langsung mengarahkan kembali ke chat
+                storage::remove(REMEMBER_KEY);
+                if let Some(history) = ctx.link().history() {
+                    history.push(Route::Login);
+                }
                 false
             }
-            Msg::ToggleDarkMode => {
-                self.dark_mode = !self.dark_mode;
+            Msg::InviteCopied(copied) => {
+                self.invite_copied = copied;
                 true
             }
-            Msg::ClearChat => {
-                self.messages.clear();
+            Msg::DeleteMessage(id) => {
+                let index = self
+                    .current_messages()
+                    .iter()
+                    .position(|m| m.id.as_ref() == Some(&id) && m.from == self.username);
+                let removed = match index {
+                    Some(index) => self.current_messages_mut().remove(index),
+                    None => return false,
+                };
+                // Pesan yang gagal terkirim tidak pernah sampai ke server
+                if removed.status != Some(DeliveryStatus::Failed) {
+                    let target = MessageRef {
+                        id,
+                        from: self.username.clone(),
+                    };
+                    self.send(&WebSocketMessage::new(
+                        MsgTypes::DeleteMessage,
+                        serde_json::to_string(&target).ok(),
+                    ));
+                }
+                true
+            }
+            Msg::ToggleReaction { id, emoji } => {
+                let username = self.username.clone();
+                let message = match self
+                    .current_messages_mut()
+                    .iter_mut()
+                    .find(|m| m.id.as_ref() == Some(&id))
+                {
+                    Some(message) => message,
+                    None => return false,
+                };
+                // Klik kedua pada emoji yang sama membatalkan reaksi
+                let message_type = if reaction::has_reacted(&message.reactions, &emoji, &username) {
+                    reaction::remove_reaction(&mut message.reactions, &emoji, &username);
+                    MsgTypes::Unreact
+                } else {
+                    reaction::add_reaction(&mut message.reactions, &emoji, &username);
+                    MsgTypes::React
+                };
+                let event = ReactionEvent {
+                    id,
+                    emoji,
+                    user: username,
+                };
+                self.send(&WebSocketMessage::new(
+                    message_type,
+                    serde_json::to_string(&event).ok(),
+                ));
                 true
             }
+            Msg::VisibilityChanged => {
+                let hidden = gloo::utils::document().hidden();
+                if hidden && self.unread_marker.is_none() {
+                    // Pergi berkali-kali tanpa membaca tetap menandai titik pertama
+                    self.unread_marker = self
+                        .current_messages()
+                        .iter()
+                        .rev()
+                        .find(|m| m.kind == MessageKind::Chat)
+                        .and_then(|m| m.id.clone());
+                }
+                if !hidden && self.unread_count > 0 {
+                    self.unread_count = 0;
+                    self.update_title();
+                }
+                self.mark_read();
+                false
+            }
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.typing_ticker = None;
+        self.clock = None;
+        gloo::utils::document().set_title(&self.base_title);
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        // Simpan riwayat room setelah tampilan tenang sejenak
+        let link = ctx.link().clone();
+        self.session_history_timeout = Some(Timeout::new(SESSION_HISTORY_DEBOUNCE_MS, move || {
+            link.send_message(Msg::SaveSessionHistory)
+        }));
+        if !self.just_added.is_empty() && self.enter_animation_timeout.is_none() {
+            let ids: Vec<String> = self.just_added.iter().cloned().collect();
+            let link = ctx.link().clone();
+            self.enter_animation_timeout = Some(Timeout::new(ENTER_ANIMATION_MS, move || {
+                link.send_message(Msg::EnterAnimationsDone(ids))
+            }));
+        }
+        if self.restore_draft {
+            self.restore_draft = false;
+            if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                input.set_value(&storage::load_string(&self.draft_key()).unwrap_or_default());
+                fit_to_content(&input);
+            }
+            let previous_len = self.input_len;
+            self.sync_input_len();
+            if self.input_len != previous_len {
+                ctx.link().send_message(Msg::DraftRestored);
+            }
+        }
+        if self.focus_search {
+            self.focus_search = false;
+            if let Some(input) = self.search_input.cast::<HtmlInputElement>() {
+                let _ = input.focus();
+            }
+        }
+        if std::mem::take(&mut self.focus_input) || first_render {
+            if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                if !other_field_focused(&input) {
+                    let _ = input.focus();
+                }
+            }
+        }
+        if let Some(previous_height) = self.history_scroll_anchor.take() {
+            if let Some(el) = self.messages_container.cast::<web_sys::Element>() {
+                el.set_scroll_top(el.scroll_top() + el.scroll_height() - previous_height);
+            }
+        }
+        if let Some(id) = self.jump_target.take() {
+            if let Some(el) = gloo::utils::document().get_element_by_id(&message_dom_id(&id)) {
+                let mut options = ScrollIntoViewOptions::new();
+                options.block(ScrollLogicalPosition::Center);
+                el.scroll_into_view_with_scroll_into_view_options(&options);
+            }
+        }
+        if self.scroll_to_bottom {
+            if let Some(el) = self.messages_container.cast::<web_sys::Element>() {
+                el.set_scroll_top(el.scroll_height());
+            }
+            self.scroll_to_bottom = false;
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some(action) = self.removed {
+            return self.render_removed(action);
+        }
         let submit = ctx.link().callback(|_| Msg::SubmitMessage);
-        let toggle_dark = ctx.link().callback(|_| Msg::ToggleDarkMode);
-        let clear_chat = ctx.link().callback(|_| Msg::ClearChat);
+        let clear_chat = ctx.link().callback(|e: MouseEvent| {
+            // Jangan sampai klik ini dianggap "klik di luar" yang membatalkan
+            e.stop_propagation();
+            Msg::RequestClear
+        });
+        let confirming_clear = self.confirm_clear.is_some();
+        let lang = self.lang;
+        let (status_dot, status_text) = match self.connection {
+            ConnectionState::Connected => ("bg-green-500", t("connected", lang)),
+            ConnectionState::Connecting => ("bg-yellow-400", t("reconnecting", lang)),
+            ConnectionState::Disconnected => ("bg-red-500", t("disconnected", lang)),
+        };
+        let disconnected = self.connection == ConnectionState::Disconnected;
+        // Pesan masih di perjalanan atau socket sedang menyambung ulang
+        let busy = self.sending.is_some() || self.connection == ConnectionState::Connecting;
         
-        // Theme classes
-        let bg_primary = if self.dark_mode { "bg-gray-900" } else { "bg-white" };
-        let bg_secondary = if self.dark_mode { "bg-gray-800" } else { "bg-gray-50" };
-        let bg_tertiary = if self.dark_mode { "bg-gray-700" } else { "bg-white" };
-        let text_primary = if self.dark_mode { "text-white" } else { "text-gray-900" };
-        let text_secondary = if self.dark_mode { "text-gray-300" } else { "text-gray-600" };
-        let border_color = if self.dark_mode { "border-gray-700" } else { "border-gray-200" };
+        let Palette {
+            bg_primary,
+            bg_secondary,
+            bg_tertiary,
+            text_primary,
+            text_secondary,
+            border_color,
+            hover_bg,
+        } = PALETTE;
+        let visible_messages = self.visible_messages();
+        let displayed_users = self.displayed_users();
+        let mut typing_names: Vec<String> = self.typing_users.keys().cloned().collect();
+        typing_names.sort_unstable();
+        let own_role = self
+            .users
+            .iter()
+            .find(|u| u.name == self.username)
+            .map(|u| u.role)
+            .unwrap_or_default();
+        let replying_to = self.replying_to.as_ref().and_then(|id| {
+            self.current_messages()
+                .iter()
+                .find(|m| m.id.as_ref() == Some(id))
+        });
+        let (window_start, window_end) = self.message_window(visible_messages.len());
+        let autocomplete_open = !self.mention_suggestions.is_empty();
+        let input_empty = self.input_len == 0;
+        let mention_selected = self.mention_selected;
+        let over_limit = self.input_len > MAX_MESSAGE_LEN;
+        let input_border = if self.over_limit_attempt {
+            "border-red-500 ring-2 ring-red-500 animate-pulse"
+        } else if over_limit {
+            "border-red-500"
+        } else {
+            border_color
+        };
+        let counter_color = if self.input_len >= MESSAGE_LEN_WARNING {
+            "text-red-500"
+        } else {
+            text_secondary
+        };
 
         html! {
-            <div class={format!("flex h-screen {}", bg_primary)}>
-                // Sidebar
-                <div class={format!("flex-none w-80 {} border-r {}", bg_secondary, border_color)}>
+            <div
+                class={format!("flex h-screen {} {}", self.theme.class(), bg_primary)}
+                onclick={confirming_clear.then(|| ctx.link().callback(|_| Msg::CancelClear))}
+            >
+                // Latar drawer sidebar di layar sempit; klik menutupnya
+                if self.sidebar_open {
+                    <div
+                        class="fixed inset-0 z-20 bg-black bg-opacity-25 md:hidden"
+                        onclick={ctx.link().callback(|_| Msg::ToggleSidebar)}
+                    ></div>
+                }
+
+                // Sidebar: drawer di bawah breakpoint md, menempel di desktop
+                <div
+                    id="sidebar"
+                    class={format!(
+                        "fixed inset-y-0 left-0 z-30 flex flex-col w-80 max-w-[85vw] transform transition-transform md:static md:z-auto md:flex-none md:max-w-none md:translate-x-0 {} {} border-r {}",
+                        if self.sidebar_open { "translate-x-0" } else { "-translate-x-full" },
+                        bg_secondary,
+                        border_color,
+                    )}
+                >
+                    // Rooms
+                    <div class={format!("p-4 border-b {}", border_color)}>
+                        <div class="flex items-center justify-between mb-2">
+                            <h2 class={format!("text-lg font-semibold {}", text_primary)}>
+                                {t("rooms", lang)}
+                            </h2>
+                            if self.invite_copied {
+                                <span class={format!("text-xs {}", text_secondary)} role="status">{t("link_copied", lang)}</span>
+                            } else if clipboard::is_supported() {
+                                <button
+                                    type="button"
+                                    onclick={ctx.link().callback(|_| Msg::CopyInviteLink)}
+                                    class={format!("text-xs hover:underline {}", text_secondary)}
+                                    title={t("copy_invite_link_hint", lang)}
+                                >
+                                    {t("copy_invite_link", lang)}
+                                </button>
+                            }
+                        </div>
+                        {
+                            self.rooms.iter().map(|room| {
+                                let active = *room == self.current_room && self.active_dm.is_none();
+                                let onclick = {
+                                    let room = room.clone();
+                                    ctx.link().callback(move |_| Msg::SwitchRoom(room.clone()))
+                                };
+                                html! {
+                                    <button
+                                        {onclick}
+                                        class={format!("block w-full text-left px-3 py-1 rounded-lg text-sm {}", if active { "bg-blue-600 text-white" } else { text_primary })}
+                                    >
+                                        {format!("# {}", room)}
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+
                     // Header sidebar
                     <div class={format!("flex items-center justify-between p-4 border-b {}", border_color)}>
                         <h2 class={format!("text-lg font-semibold {}", text_primary)}>
-                            {"Online Users"}
+                            {t("online_users", lang)}
                         </h2>
                         <span class={format!("bg-green-500 text-white text-xs px-2 py-1 rounded-full")}>
-                            {self.users.len()}
+                            {if self.user_filter.trim().is_empty() {
+                                self.users.len().to_string()
+                            } else {
+                                format!("{}/{}", displayed_users.len(), self.users.len())
+                            }}
                         </span>
                     </div>
+
+                    // Filter dan urutan daftar user
+                    <div class={format!("flex px-4 py-2 space-x-2 border-b {}", border_color)}>
+                        <input
+                            type="search"
+                            placeholder={t("filter_users_placeholder", lang)}
+                            aria-label={t("filter_users", lang)}
+                            value={self.user_filter.clone()}
+                            oninput={ctx.link().callback(|e: InputEvent| {
+                                let input: HtmlInputElement = e.target_unchecked_into();
+                                Msg::UserFilterChanged(input.value())
+                            })}
+                            class={format!("flex-1 min-w-0 px-2 py-1 text-sm border rounded-lg {} {} {}", bg_primary, text_primary, border_color)}
+                        />
+                        <select
+                            onchange={ctx.link().batch_callback(|e: Event| {
+                                let select: HtmlSelectElement = e.target_unchecked_into();
+                                UserSort::from_key(&select.value()).map(Msg::SetUserSort)
+                            })}
+                            class={format!("px-2 py-1 text-sm border rounded-lg {} {} {}", bg_primary, text_primary, border_color)}
+                            title={t("sort_users", lang)}
+                            aria-label={t("sort_users", lang)}
+                        >
+                            {
+                                UserSort::ALL.into_iter().map(|sort| html! {
+                                    <option value={sort.key()} selected={sort == self.user_sort}>
+                                        {sort.label(lang)}
+                                    </option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </div>
                     
                     // Users list
-                    <div class="overflow-y-auto h-full pb-20">
+                    <div class="overflow-y-auto flex-1 pb-20">
                         {
-                            if self.users.is_empty() {
+                            if self.users.is_empty() && self.is_loading() {
+                                (0..SKELETON_ROWS).map(|_| html! {
+                                    <div class={format!("flex items-center p-3 m-3 {} rounded-lg animate-pulse", bg_tertiary)}>
+                                        <div class="w-10 h-10 mr-3 rounded-full bg-gray-400 opacity-40"></div>
+                                        <div class="flex-1 space-y-2">
+                                            <div class="h-3 w-2/3 rounded bg-gray-400 opacity-40"></div>
+                                            <div class="h-2 w-1/3 rounded bg-gray-400 opacity-40"></div>
+                                        </div>
+                                    </div>
+                                }).collect::<Html>()
+                            } else if self.users.is_empty() && self.load_timed_out {
+                                html! {
+                                    <div class={format!("flex items-center justify-center h-32 {}", text_secondary)}>
+                                        <div class="text-center">
+                                            <div class="text-2xl mb-2">{"⚠️"}</div>
+                                            <div class="text-sm">{t("users_load_failed", lang)}</div>
+                                        </div>
+                                    </div>
+                                }
+                            } else if self.users.is_empty() {
                                 html! {
                                     <div class={format!("flex items-center justify-center h-32 {}", text_secondary)}>
                                         <div class="text-center">
                                             <div class="text-2xl mb-2">{"😴"}</div>
-                                            <div class="text-sm">{"No users online"}</div>
+                                            <div class="text-sm">{t("no_users", lang)}</div>
                                         </div>
                                     </div>
                                 }
+                            } else if displayed_users.is_empty() {
+                                html! {
+                                    <div class={format!("flex items-center justify-center h-32 text-sm {}", text_secondary)}>
+                                        {t("no_matching_users", lang)}
+                                    </div>
+                                }
                             } else {
-                                self.users.iter().map(|u| {
+                                let items = displayed_users.iter().map(|u| {
+                                    let is_self = u.name == self.username;
+                                    let active = self.active_dm.as_ref() == Some(&u.name);
+                                    let unread = self.dm_unread.get(&u.name).copied().unwrap_or(0);
+                                    let muted = self.muted_users.contains(&u.name);
+                                    let can_moderate = !is_self && own_role.can_moderate(u.role);
+                                    // Klik user lain (atau Enter/Spasi saat fokus) membuka DM dengannya
+                                    let onclick = (!is_self).then(|| {
+                                        let name = u.name.clone();
+                                        ctx.link().callback(move |_| Msg::OpenDm(name.clone()))
+                                    });
+                                    // Panah atas/bawah, Home dan End berpindah antar baris
+                                    let onkeydown = {
+                                        let name = (!is_self).then(|| u.name.clone());
+                                        ctx.link().batch_callback(move |e: KeyboardEvent| {
+                                            if move_row_focus(&e) {
+                                                e.prevent_default();
+                                                return None;
+                                            }
+                                            let name = name.clone()?;
+                                            matches!(e.key().as_str(), "Enter" | " ").then(|| {
+                                                e.prevent_default();
+                                                Msg::OpenDm(name)
+                                            })
+                                        })
+                                    };
                                     html!{
-                                        <div class={format!("flex items-center p-3 m-3 {} rounded-lg shadow-sm hover:shadow-md transition-shadow", bg_tertiary)}>
-                                            <div 
-                                                class="w-10 h-10 rounded-full flex items-center justify-center text-white font-bold text-sm mr-3"
-                                                style={format!("background-color: {}", u.color)}
-                                            >
-                                                {u.name.chars().next().unwrap_or('?').to_uppercase()}
-                                            </div>
+                                        <li
+                                            {onclick}
+                                            {onkeydown}
+                                            role={(!is_self).then_some("button")}
+                                            // Baris sendiri hanya bisa dicapai lewat tombol panah
+                                            tabindex={if is_self { "-1" } else { "0" }}
+                                            aria-current={active.then_some("true")}
+                                            title={if is_self { t("you", lang).to_string() } else { t("message_privately", lang).replace("{name}", &u.name) }}
+                                            class={format!("relative flex items-center p-3 m-3 {} rounded-lg shadow-sm hover:shadow-md transition-shadow outline-none focus-visible:ring-4 focus-visible:ring-blue-300 {} {}", bg_tertiary, if is_self { "" } else { "cursor-pointer" }, if active { "ring-2 ring-blue-500" } else { "" })}
+                                        >
+                                            {self.render_avatar(ctx, &u.name, &u.color, &u.avatar, "w-10 h-10 text-sm mr-3")}
                                             <div class="flex-1">
-                                                <div class={format!("font-medium {}", text_primary)}>
+                                                <div class={format!("font-medium {}", u.role.name_class().unwrap_or(text_primary))}>
                                                     {u.name.clone()}
+                                                    {render_role_badge(u.role, lang)}
+                                                    if is_self {
+                                                        <span class={format!("ml-1 text-xs font-normal {}", text_secondary)}>{t("you_suffix", lang)}</span>
+                                                    }
+                                                    if muted {
+                                                        <span class={format!("ml-1 text-xs font-normal {}", text_secondary)}>{t("muted_suffix", lang)}</span>
+                                                    }
                                                 </div>
                                                 <div class={format!("text-xs {}", text_secondary)}>
-                                                    {"🟢 Online"}
+                                                    {match u.status {
+                                                        // Belum dikonfirmasi daftar Users dari server
+                                                        _ if is_self && !self.users_loaded => t("joining", lang),
+                                                        Presence::Online => t("status_online", lang),
+                                                        Presence::Away => t("status_away", lang),
+                                                    }}
                                                 </div>
                                             </div>
-                                        </div>
+                                            if unread > 0 {
+                                                <span
+                                                    class="bg-blue-600 text-white text-xs px-2 py-1 rounded-full"
+                                                    title={t("unread_private", lang)}
+                                                    aria-label={t("unread_private_count", lang).replace("{count}", &unread.to_string())}
+                                                >
+                                                    {unread}
+                                                </span>
+                                            }
+                                            if !is_self {
+                                                <button
+                                                    type="button"
+                                                    onclick={ctx.link().callback({
+                                                        let name = u.name.clone();
+                                                        move |e: MouseEvent| {
+                                                            // Jangan ikut membuka DM
+                                                            e.stop_propagation();
+                                                            Msg::ToggleMuteUser(name.clone())
+                                                        }
+                                                    })}
+                                                    onkeydown={Callback::from(|e: KeyboardEvent| e.stop_propagation())}
+                                                    class={format!("ml-2 p-1 rounded {} {}", hover_bg, text_secondary)}
+                                                    title={t(if muted { "unmute_user" } else { "mute_user" }, lang)}
+                                                    aria-label={t(if muted { "unmute_user" } else { "mute_user" }, lang)}
+                                                    aria-pressed={muted.to_string()}
+                                                >
+                                                    {if muted { "🔈" } else { "🔇" }}
+                                                </button>
+                                            }
+                                            if can_moderate {
+                                                {self.render_moderation_menu(ctx, &u.name)}
+                                            }
+                                        </li>
                                     }
-                                }).collect::<Html>()
+                                }).collect::<Html>();
+                                html! {
+                                    <ul aria-label={t("online_users", lang)}>{items}</ul>
+                                }
                             }
                         }
+                        {self.render_recently_offline()}
                     </div>
+
+                    {self.render_profile_settings(ctx)}
                 </div>
 
                 // Main chat area
-                <div class="flex-1 flex flex-col">
+                <div class="flex-1 flex flex-col min-w-0">
                     // Chat header
                     <div class={format!("flex items-center justify-between p-4 border-b {} {}", border_color, bg_tertiary)}>
-                        <div class="flex items-center">
-                            <h1 class={format!("text-xl font-bold {}", text_primary)}>
-                                {"💬 Chat Room"}
+                        <div class="flex items-center min-w-0">
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::ToggleSidebar)}
+                                class={format!("mr-3 p-2 rounded-lg md:hidden {} {} transition-colors", text_primary, hover_bg)}
+                                title={t("toggle_sidebar", lang)}
+                                aria-label={t("toggle_sidebar", lang)}
+                                aria-controls="sidebar"
+                                aria-expanded={self.sidebar_open.to_string()}
+                            >
+                                {"☰"}
+                            </button>
+                            <h1 class={format!("text-xl font-bold truncate {}", text_primary)}>
+                                {match &self.active_dm {
+                                    Some(peer) => format!("🔒 {}", peer),
+                                    None => format!("💬 # {}", self.current_room),
+                                }}
                             </h1>
-                            <span class={format!("ml-3 text-sm {} bg-blue-100 dark:bg-blue-900 px-2 py-1 rounded", text_secondary)}>
-                                {format!("{} messages", self.messages.len())}
+                            <span class={format!("ml-3 flex items-center text-xs {}", text_secondary)}>
+                                <span class={format!("w-2 h-2 rounded-full mr-1 {}", status_dot)} aria-hidden="true"></span>
+                                {status_text}
+                            </span>
+                            <span class={format!("hidden sm:inline ml-3 text-sm {} bg-blue-100 dark:bg-blue-900 px-2 py-1 rounded", text_secondary)}>
+                                {t("message_count", lang).replace("{count}", &self.current_messages().len().to_string())}
                             </span>
                         </div>
                         
                         <div class="flex items-center space-x-2">
+                            // Search toggle
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::ToggleSearch)}
+                                class={format!("p-2 rounded-lg {} {} transition-colors", text_primary, hover_bg)}
+                                title={t("search_messages", lang)}
+                                aria-label={t("search_messages", lang)}
+                                aria-expanded={self.search_open.to_string()}
+                            >
+                                {"🔍"}
+                            </button>
+
                             // Clear chat button
                             <button 
                                 onclick={clear_chat}
-                                class="p-2 rounded-lg bg-red-500 hover:bg-red-600 text-white transition-colors"
-                                title="Clear Chat"
+                                class={format!("p-2 rounded-lg text-white transition-colors {}", if confirming_clear { "bg-red-700 ring-2 ring-red-300 text-sm font-medium" } else { "bg-red-500 hover:bg-red-600" })}
+                                title={if confirming_clear { t("confirm_clear_hint", lang) } else { t("clear_chat", lang) }}
+                                aria-label={if confirming_clear { t("confirm_clear_hint", lang) } else { t("clear_chat", lang) }}
                             >
-                                {"🗑️"}
+                                {if confirming_clear { t("confirm_clear", lang) } else { "🗑️" }}
                             </button>
                             
-                            // Dark mode toggle
-                            <button 
-                                onclick={toggle_dark}
-                                class={format!("p-2 rounded-lg {} hover:bg-gray-200 dark:hover:bg-gray-600 transition-colors", text_primary)}
-                                title="Toggle Dark Mode"
+                            // Settings (tema, suara, tampilan)
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::ToggleSettings)}
+                                class={format!("p-2 rounded-lg {} {} transition-colors", text_primary, hover_bg)}
+                                title={t("settings", lang)}
+                                aria-label={t("settings", lang)}
+                                aria-expanded={self.settings_open.to_string()}
                             >
-                                {if self.dark_mode { "☀️" } else { "🌙" }}
+                                {"⚙️"}
                             </button>
                         </div>
                     </div>
 
+                    if disconnected && !self.reconnect_banner_dismissed {
+                        <div class="flex items-center px-4 py-2 text-sm text-white bg-red-500" role="alert">
+                            <span class="flex-1">{t("connection_lost", lang)}</span>
+                            <button
+                                type="button"
+                                onclick={ctx.link().callback(|_| Msg::ReconnectNow)}
+                                class="px-3 py-1 mr-2 text-xs font-medium text-red-600 bg-white rounded-lg hover:bg-red-50"
+                            >
+                                {t("reconnect_now", lang)}
+                            </button>
+                            <button
+                                type="button"
+                                onclick={ctx.link().callback(|_| Msg::DismissReconnectBanner)}
+                                class="px-2 hover:opacity-75"
+                                title={t("dismiss", lang)}
+                                aria-label={t("dismiss", lang)}
+                            >
+                                {"✕"}
+                            </button>
+                        </div>
+                    }
+
+                    // Search bar
+                    if self.search_open {
+                        <div class={format!("flex px-4 py-2 space-x-2 border-b {} {}", border_color, bg_tertiary)}>
+                            <input
+                                ref={self.search_input.clone()}
+                                type="search"
+                                autofocus=true
+                                placeholder={t("search_messages_placeholder", lang)}
+                                aria-label={t("search_messages", lang)}
+                                value={self.search_query.clone()}
+                                oninput={ctx.link().callback(|e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    Msg::SearchChanged(input.value())
+                                })}
+                                onkeydown={ctx.link().batch_callback(|e: KeyboardEvent| {
+                                    (e.key() == "Enter").then_some(Msg::SearchAll)
+                                })}
+                                class={format!("flex-1 min-w-0 px-3 py-2 text-sm border rounded-lg {} {} {}", bg_primary, text_primary, border_color)}
+                            />
+                            <button
+                                type="button"
+                                onclick={ctx.link().callback(|_| Msg::SearchAll)}
+                                disabled={self.search_query.trim().is_empty()}
+                                class="px-3 py-2 text-sm font-medium text-white bg-blue-500 rounded-lg hover:bg-blue-600 disabled:opacity-50 disabled:cursor-not-allowed"
+                                title={t("search_all_hint", lang)}
+                            >
+                                {t("search_all", lang)}
+                            </button>
+                        </div>
+                        {self.render_server_search(ctx)}
+                    }
+
+                    {self.render_pinned_banner(ctx)}
+
                     // Messages area
-                    <div class={format!("flex-1 overflow-y-auto p-4 {}", bg_primary)}>
+                    <div
+                        ref={self.messages_container.clone()}
+                        role="log"
+                        aria-live="polite"
+                        aria-label={t("messages", lang)}
+                        aria-busy={self.history_loading.to_string()}
+                        tabindex="0"
+                        onscroll={ctx.link().callback(|e: Event| {
+                            let el: web_sys::Element = e.target_unchecked_into();
+                            Msg::Scrolled(el.scroll_top() as f64)
+                        })}
+                        ondragenter={ctx.link().callback(|e: DragEvent| {
+                            e.prevent_default();
+                            Msg::DragEnter
+                        })}
+                        ondragleave={ctx.link().callback(|_: DragEvent| Msg::DragLeave)}
+                        // Tanpa preventDefault di dragover, browser tidak mengizinkan drop
+                        ondragover={Callback::from(|e: DragEvent| e.prevent_default())}
+                        ondrop={ctx.link().callback(|e: DragEvent| {
+                            e.prevent_default();
+                            let file = e.data_transfer().and_then(|dt| dt.files()).and_then(|files| files.get(0));
+                            Msg::DropFile(file)
+                        })}
+                        class={format!("flex-1 overflow-y-auto p-4 {}", bg_primary)}
+                    >
+                        if self.drag_depth > 0 {
+                            <div class="fixed inset-0 z-40 flex items-center justify-center m-4 border-4 border-dashed border-blue-500 rounded-lg bg-blue-500 bg-opacity-10 pointer-events-none">
+                                <span class="px-4 py-2 text-lg font-medium text-white bg-blue-600 rounded-lg shadow-lg">{t("drop_image", lang)}</span>
+                            </div>
+                        }
                         {
-                            if self.messages.is_empty() {
+                            if self.current_messages().is_empty() && self.is_loading() {
+                                (0..SKELETON_ROWS).map(|i| html! {
+                                    <div class={format!("flex items-start mt-4 px-4 py-4 max-w-md rounded-lg animate-pulse {}", bg_tertiary)}>
+                                        <div class="w-8 h-8 mr-3 rounded-full bg-gray-400 opacity-40"></div>
+                                        <div class="flex-1 space-y-2">
+                                            <div class="h-3 w-1/4 rounded bg-gray-400 opacity-40"></div>
+                                            <div class={format!("h-3 rounded bg-gray-400 opacity-40 {}", if i % 2 == 0 { "w-3/4" } else { "w-1/2" })}></div>
+                                        </div>
+                                    </div>
+                                }).collect::<Html>()
+                            } else if self.current_messages().is_empty() && self.load_timed_out {
+                                html! {
+                                    <div class={format!("flex items-center justify-center h-full {}", text_secondary)}>
+                                        <div class="text-center">
+                                            <div class="text-4xl mb-4">{"⚠️"}</div>
+                                            <div class="text-lg">{t("chat_load_failed", lang)}</div>
+                                            <div class="text-sm mt-2">{t("chat_load_retry", lang)}</div>
+                                        </div>
+                                    </div>
+                                }
+                            } else if self.current_messages().is_empty() {
                                 html! {
                                     <div class={format!("flex items-center justify-center h-full {}", text_secondary)}>
                                         <div class="text-center">
                                             <div class="text-4xl mb-4">{"💭"}</div>
-                                            <div class="text-lg">{"No messages yet"}</div>
-                                            <div class="text-sm mt-2">{"Start a conversation!"}</div>
+                                            <div class="text-lg">{t("no_messages", lang)}</div>
+                                            <div class="text-sm mt-2">
+                                                {match &self.active_dm {
+                                                    Some(peer) => t("dm_private_hint", lang).replace("{name}", peer),
+                                                    None => t("start_conversation", lang).to_string(),
+                                                }}
+                                            </div>
+                                        </div>
+                                    </div>
+                                }
+                            } else if visible_messages.is_empty() {
+                                html! {
+                                    <div class={format!("flex items-center justify-center h-full {}", text_secondary)}>
+                                        <div class="text-center">
+                                            <div class="text-4xl mb-4">{"🔍"}</div>
+                                            <div class="text-lg">{t("no_matching_messages", lang)}</div>
                                         </div>
                                     </div>
                                 }
                             } else {
-                                self.messages.iter().map(|m| {
-                                    let user = self.users.iter().find(|u| u.name == m.from);
-                                    let user_color = user.map(|u| u.color.clone()).unwrap_or_else(|| Self::get_user_color(&m.from));
-                                    
-                                    html!{
-                                        <div class="mb-4 max-w-3xl">
-                                            <div class={format!("flex items-start p-4 {} rounded-lg shadow-sm", bg_tertiary)}>
-                                                <div 
-                                                    class="w-8 h-8 rounded-full flex items-center justify-center text-white font-bold text-xs mr-3 flex-shrink-0"
-                                                    style={format!("background-color: {}", user_color)}
-                                                >
-                                                    {m.from.chars().next().unwrap_or('?').to_uppercase()}
-                                                </div>
-                                                <div class="flex-1 min-w-0">
-                                                    <div class={format!("font-medium text-sm mb-1 {}", text_primary)}>
-                                                        {m.from.clone()}
-                                                    </div>
-                                                    <div class={format!("text-sm {}", text_primary)}>
-                                                        if m.message.ends_with(".gif") || m.message.ends_with(".jpg") || m.message.ends_with(".png") {
-                                                            <img class="mt-2 max-w-xs rounded-lg" src={m.message.clone()} alt="Image"/>
-                                                        } else {
-                                                            {m.message.clone()}
-                                                        }
-                                                    </div>
-                                                </div>
+                                html! {
+                                    <>
+                                        if self.history_loading {
+                                            <div class={format!("py-2 text-center text-xs {}", text_secondary)}>
+                                                {t("loading_older", lang)}
                                             </div>
-                                        </div>
-                                    }
-                                }).collect::<Html>()
+                                        }
+                                        <div style={format!("height: {}px", window_start as f64 * ESTIMATED_ROW_HEIGHT)}></div>
+                                        {self.render_message_rows(ctx, &visible_messages, window_start..window_end)}
+                                        <div style={format!("height: {}px", (visible_messages.len() - window_end) as f64 * ESTIMATED_ROW_HEIGHT)}></div>
+                                    </>
+                                }
                             }
                         }
                     </div>
 
+                    // Tombol kembali ke dasar, menempel di pojok kanan bawah area pesan
+                    if !self.pinned_to_bottom && !visible_messages.is_empty() {
+                        <div class="relative">
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::JumpToBottom)}
+                                class="absolute bottom-4 right-6 px-3 py-2 text-sm font-medium text-white bg-blue-600 hover:bg-blue-700 rounded-full shadow-lg transition-colors"
+                                title={t("scroll_to_bottom", lang)}
+                                aria-label={if self.unseen_count > 0 { t("scroll_to_bottom_new", lang).replace("{count}", &self.unseen_count.to_string()) } else { t("scroll_to_bottom", lang).to_string() }}
+                            >
+                                {if self.unseen_count > 0 { t("new_count", lang).replace("{count}", &self.unseen_count.to_string()) } else { "↓".to_string() }}
+                            </button>
+                        </div>
+                    }
+
+                    // Typing indicator
+                    <div class={format!("h-6 px-4 text-xs italic {} {}", bg_primary, text_secondary)} aria-live="polite">
+                        if !typing_names.is_empty() {
+                            {typing_label(&typing_names, lang)}
+                            // Titik-titik bergantian berdenyut sebagai elipsis
+                            <span aria-hidden="true">
+                                {
+                                    [0, 200, 400].iter().map(|delay| html! {
+                                        <span class="animate-pulse motion-reduce:animate-none" style={format!("animation-delay: {}ms", delay)}>{"."}</span>
+                                    }).collect::<Html>()
+                                }
+                            </span>
+                        }
+                    </div>
+
                     // Input area
                     <div class={format!("p-4 border-t {} {}", border_color, bg_tertiary)}>
+                        if self.editing.is_some() {
+                            <div class={format!("flex items-center mb-2 pl-2 text-xs border-l-2 border-yellow-500 {}", text_secondary)}>
+                                <span class="flex-1 font-medium">{t("editing_message", lang)}</span>
+                                <button
+                                    type="button"
+                                    onclick={ctx.link().callback(|_| Msg::CancelEdit)}
+                                    class="px-2 hover:opacity-75"
+                                    title={t("cancel_edit", lang)}
+                                    aria-label={t("cancel_edit", lang)}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        }
+                        if let Some(original) = replying_to {
+                            <div class={format!("flex items-center mb-2 pl-2 text-xs border-l-2 border-blue-500 {}", text_secondary)}>
+                                <span class="flex-1 truncate">
+                                    <span class="font-medium">{t("replying_to", lang).replace("{name}", &original.from)}</span>
+                                    {snippet(&original.message, REPLY_SNIPPET_CHARS)}
+                                </span>
+                                <button
+                                    type="button"
+                                    onclick={ctx.link().callback(|_| Msg::ReplyTo(None))}
+                                    class="px-2 hover:opacity-75"
+                                    title={t("cancel_reply", lang)}
+                                    aria-label={t("cancel_reply", lang)}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        }
                         <div class="flex items-end space-x-3">
-                            <div class="flex-1">
-                                <input 
-                                    ref={self.chat_input.clone()} 
-                                    type="text" 
-                                    placeholder="Type your message..." 
-                                    class={format!("w-full px-4 py-3 {} {} border {} rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent resize-none transition-colors", bg_primary, text_primary, border_color)}
-                                    onkeypress={ctx.link().callback(|e: KeyboardEvent| {
+                            <div class="flex-1 relative">
+                                {self.render_mention_suggestions(ctx)}
+                                <textarea
+                                    ref={self.chat_input.clone()}
+                                    rows="1"
+                                    placeholder={t("message_placeholder", lang)}
+                                    aria-label={t("message_input", lang)}
+                                    class={format!("block w-full max-h-40 pl-4 pr-10 py-3 {} {} border {} rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent resize-none transition-colors", bg_primary, text_primary, input_border)}
+                                    oninput={ctx.link().callback(|e: InputEvent| {
+                                        fit_to_content(&e.target_unchecked_into());
+                                        Msg::InputChanged
+                                    })}
+                                    onkeydown={ctx.link().batch_callback(move |e: KeyboardEvent| {
+                                        if !autocomplete_open {
+                                            return (e.key() == "ArrowUp" && input_empty).then(|| {
+                                                e.prevent_default();
+                                                Msg::EditLast
+                                            });
+                                        }
+                                        // Selain itu hanya menangani tombol saat dropdown mention terbuka
+                                        let msg = match e.key().as_str() {
+                                            "ArrowDown" => Msg::MentionNavigate(1),
+                                            "ArrowUp" => Msg::MentionNavigate(-1),
+                                            "Enter" | "Tab" => Msg::CompleteMention(mention_selected),
+                                            "Escape" => Msg::DismissMention,
+                                            _ => return None,
+                                        };
+                                        e.prevent_default();
+                                        Some(msg)
+                                    })}
+                                    onkeyup={ctx.link().batch_callback(|e: KeyboardEvent| {
+                                        matches!(e.key().as_str(), "ArrowLeft" | "ArrowRight" | "Home" | "End")
+                                            .then_some(Msg::CaretMoved)
+                                    })}
+                                    onclick={ctx.link().callback(|_| Msg::CaretMoved)}
+                                    onkeypress={ctx.link().batch_callback(|e: KeyboardEvent| {
+                                        // Enter mengirim, Shift+Enter menambah baris baru
                                         if e.key() == "Enter" && !e.shift_key() {
                                             e.prevent_default();
-                                            Msg::SubmitMessage
+                                            Some(Msg::SubmitMessage)
                                         } else {
-                                            return Msg::HandleMsg("".to_string()); // Dummy message
+                                            None
                                         }
                                     })}
                                 />
+                                if self.input_len > 0 {
+                                    <button
+                                        type="button"
+                                        onclick={ctx.link().callback(|_| Msg::ClearInput)}
+                                        class={format!("absolute top-2 right-2 w-7 h-7 rounded-full {} {}", hover_bg, text_secondary)}
+                                        title={t("clear_input", lang)}
+                                        aria-label={t("clear_input", lang)}
+                                    >
+                                        {"✕"}
+                                    </button>
+                                }
+                                <div class="flex justify-between mt-1 text-xs">
+                                    <span class="text-red-500" role="status">
+                                        if self.slow_down.is_some() {
+                                            {t("slow_down", lang)}
+                                        }
+                                    </span>
+                                    <span class={counter_color}>
+                                        {format!("{}/{}", self.input_len, MAX_MESSAGE_LEN)}
+                                    </span>
+                                </div>
                             </div>
+                            <div class="relative">
+                                <button
+                                    type="button"
+                                    onclick={ctx.link().callback(|_| Msg::ToggleEmojiPicker)}
+                                    class={format!("p-3 rounded-lg border {} {} transition-colors", border_color, hover_bg)}
+                                    title={t("insert_emoji", lang)}
+                                    aria-label={t("insert_emoji", lang)}
+                                    aria-haspopup="dialog"
+                                    aria-expanded={self.emoji_picker_open.to_string()}
+                                >
+                                    {"😀"}
+                                </button>
+                                {self.render_emoji_picker(ctx)}
+                            </div>
+                            if gif::is_configured() {
+                                <div class="relative">
+                                    <button
+                                        type="button"
+                                        onclick={ctx.link().callback(|_| Msg::ToggleGifPicker)}
+                                        class={format!("p-3 text-sm font-bold rounded-lg border {} {} {} transition-colors", border_color, text_primary, hover_bg)}
+                                        title={t("send_gif", lang)}
+                                        aria-label={t("send_gif", lang)}
+                                        aria-haspopup="dialog"
+                                        aria-expanded={self.gif_picker_open.to_string()}
+                                    >
+                                        {"GIF"}
+                                    </button>
+                                    {self.render_gif_picker(ctx)}
+                                </div>
+                            }
                             <button 
                                 onclick={submit}
-                                class="px-6 py-3 bg-blue-600 hover:bg-blue-700 text-white rounded-lg font-medium transition-colors flex items-center space-x-2"
+                                disabled={disconnected || busy}
+                                aria-label={t("send_message", lang)}
+                                aria-busy={busy.to_string()}
+                                class="px-6 py-3 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed text-white rounded-lg font-medium transition-colors flex items-center space-x-2"
                             >
-                                <span>{"Send"}</span>
-                                <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 19l9 2-9-18-9 18 9-2zm0 0v-8"></path>
-                                </svg>
+                                <span>{t("send", lang)}</span>
+                                if busy {
+                                    <svg class="w-4 h-4 animate-spin" fill="none" viewBox="0 0 24 24" aria-hidden="true">
+                                        <circle class="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" stroke-width="4"></circle>
+                                        <path class="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8v4a4 4 0 00-4 4H4z"></path>
+                                    </svg>
+                                } else {
+                                    <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24" aria-hidden="true">
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 19l9 2-9-18-9 18 9-2zm0 0v-8"></path>
+                                    </svg>
+                                }
                             </button>
                         </div>
                     </div>
                 </div>
+
+                {self.render_undo_snackbar(ctx)}
+                {self.render_moderation_confirm(ctx)}
+                {self.render_context_menu(ctx)}
+                <Toasts toasts={self.toasts.clone()} lang={self.lang} on_dismiss={ctx.link().callback(Msg::DismissToast)} />
+                {self.render_settings_panel(ctx)}
             </div>
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_frame_rejects_invalid_json() {
+        assert!(parse_frame("not json").is_err());
+        assert!(parse_frame("{\"messageType\":").is_err());
+        assert!(parse_frame("{\"data\":\"hi\"}").is_err());
+    }
+
+    #[test]
+    fn parse_frame_accepts_known_types() {
+        let msg = parse_frame(r#"{"messageType":"message","data":"{}"}"#)
+            .unwrap()
+            .unwrap();
+        assert!(msg.message_type == MsgTypes::Message);
+        assert_eq!(msg.data.as_deref(), Some("{}"));
+    }
+
+    #[test]
+    fn parse_frame_data_requires_data() {
+        let msg = parse_frame(r#"{"messageType":"message"}"#)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            parse_frame_data::<MessageData>(msg.data.as_deref()),
+            Err(FrameError::MissingData)
+        ));
+    }
+
+    #[test]
+    fn parse_frame_data_rejects_bad_inner_json() {
+        for data in ["not json", "{\"from\":42}", "[]"] {
+            assert!(matches!(
+                parse_frame_data::<MessageData>(Some(data)),
+                Err(FrameError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_frame_data_reads_message() {
+        let message: MessageData =
+            parse_frame_data(Some(r#"{"from":"alice","message":"hi","id":"1"}"#)).unwrap();
+        assert_eq!(message.from, "alice");
+        assert_eq!(message.message, "hi");
+        assert_eq!(message.id.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn relative_time_boundaries() {
+        let now = 1_700_000_000_000.0;
+        assert_eq!(relative_time(now, now, Lang::En), "just now");
+        assert_eq!(relative_time(now - 59_999.0, now, Lang::En), "just now");
+        assert_eq!(relative_time(now - 60_000.0, now, Lang::En), "1m ago");
+        assert_eq!(
+            relative_time(now - 59.0 * 60_000.0, now, Lang::En),
+            "59m ago"
+        );
+        assert_eq!(
+            relative_time(now - 60.0 * 60_000.0, now, Lang::En),
+            "1h ago"
+        );
+        assert_eq!(
+            relative_time(now - 24.0 * 3_600_000.0, now, Lang::En),
+            "1d ago"
+        );
+        // Jam client yang sedikit tertinggal tidak menghasilkan waktu negatif
+        assert_eq!(relative_time(now + 5_000.0, now, Lang::En), "just now");
+    }
+
+    fn day(year: i32, month: u32, day: u32) -> LocalDay {
+        LocalDay { year, month, day }
+    }
+
+    #[test]
+    fn day_label_today_and_yesterday() {
+        let today = day(2024, 5, 15);
+        assert_eq!(local_day_label(today, today, Lang::En), "Today");
+        assert_eq!(
+            local_day_label(day(2024, 5, 14), today, Lang::En),
+            "Yesterday"
+        );
+        // Melewati batas bulan, tahun kabisat, dan tahun
+        assert_eq!(
+            local_day_label(day(2024, 1, 29), day(2024, 2, 1), Lang::En),
+            "Yesterday"
+        );
+        assert_eq!(
+            local_day_label(day(2023, 1, 28), day(2023, 2, 1), Lang::En),
+            "Yesterday"
+        );
+        assert_eq!(
+            local_day_label(day(2023, 11, 31), day(2024, 0, 1), Lang::En),
+            "Yesterday"
+        );
+    }
+
+    #[test]
+    fn day_label_older_dates() {
+        let today = day(2024, 5, 15);
+        assert_eq!(
+            local_day_label(day(2024, 5, 13), today, Lang::En),
+            "June 13"
+        );
+        assert_eq!(local_day_label(day(2024, 2, 3), today, Lang::En), "March 3");
+        assert_eq!(
+            local_day_label(day(2023, 2, 3), today, Lang::En),
+            "March 3, 2023"
+        );
+        assert_eq!(
+            local_day_label(day(2023, 5, 14), today, Lang::En),
+            "June 14, 2023"
+        );
+    }
+
+    #[test]
+    fn day_label_in_indonesian() {
+        let today = day(2024, 5, 14);
+        assert_eq!(local_day_label(today, today, Lang::Id), "Hari ini");
+        assert_eq!(local_day_label(day(2024, 2, 3), today, Lang::Id), "3 Maret");
+        assert_eq!(
+            local_day_label(day(2023, 7, 17), today, Lang::Id),
+            "17 Agustus 2023"
+        );
+        assert_eq!(relative_time(0.0, 5.0 * 60_000.0, Lang::Id), "5 mnt lalu");
+    }
+
+    #[test]
+    fn format_time_pads_hours_and_minutes() {
+        assert_eq!(format_clock(9, 5), "09:05");
+        assert_eq!(format_clock(0, 0), "00:00");
+        assert_eq!(format_clock(23, 59), "23:59");
+    }
+
+    #[test]
+    fn format_time_of_invalid_timestamp_is_empty() {
+        assert_eq!(format_time(f64::NAN), "");
+        assert_eq!(format_time(f64::INFINITY), "");
+        assert_eq!(format_time(-f64::INFINITY), "");
+        assert_eq!(format_time(MAX_TIME_MS + 1.0), "");
+        assert_eq!(format_date(f64::NAN), "");
+        assert!(is_valid_time(0.0));
+        assert!(is_valid_time(-MAX_TIME_MS));
+    }
+
+    fn user(name: &str, color: &str, avatar: &str) -> UserProfile {
+        UserProfile {
+            name: name.to_string(),
+            color: color.to_string(),
+            avatar: avatar.to_string(),
+            status: Presence::Online,
+            role: Role::User,
+        }
+    }
+
+    #[test]
+    fn merge_users_keeps_existing_profiles_when_someone_joins() {
+        let known = vec![user("alice", "#123456", "cat")];
+        let names = vec!["alice".to_string(), "bob".to_string()];
+        let own = OwnProfile {
+            name: "me",
+            color: None,
+            avatar: None,
+        };
+        let users = merge_user_profiles(&known, &names, &HashMap::new(), &HashMap::new(), &own);
+        assert_eq!(users[0], known[0]);
+        assert_eq!(users[1], user("bob", &Chat::get_user_color("bob"), "bob"));
+    }
+
+    #[test]
+    fn merge_users_applies_new_profiles_and_own_fallback() {
+        let known = vec![user("alice", "#123456", "cat")];
+        let names = vec!["alice".to_string(), "me".to_string()];
+        let profiles = HashMap::from([(
+            "alice".to_string(),
+            Profile {
+                color: Some("#abcdef".to_string()),
+                avatar: None,
+                role: Role::default(),
+            },
+        )]);
+        let own = OwnProfile {
+            name: "me",
+            color: Some("#000000".to_string()),
+            avatar: Some("dog".to_string()),
+        };
+        let users = merge_user_profiles(&known, &names, &profiles, &HashMap::new(), &own);
+        assert_eq!(users[0], user("alice", "#abcdef", "cat"));
+        assert_eq!(users[1], user("me", "#000000", "dog"));
+    }
+
+    fn chat_message(from: &str, message: &str) -> MessageData {
+        MessageData {
+            from: from.to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn export_text_writes_one_line_per_message() {
+        let messages = vec![
+            chat_message("alice", "hi"),
+            MessageData {
+                kind: MessageKind::System,
+                ..chat_message("", "bob joined")
+            },
+            chat_message("bob", "/me waves"),
+        ];
+        assert_eq!(
+            export_messages(&messages, ExportFormat::Text),
+            "alice: hi\n* bob waves\n"
+        );
+    }
+
+    #[test]
+    fn export_json_round_trips() {
+        let messages = vec![
+            MessageData {
+                id: Some("1".to_string()),
+                timestamp: Some(1_700_000_000_000.0),
+                ..chat_message("alice", "hi")
+            },
+            MessageData {
+                kind: MessageKind::System,
+                ..chat_message("", "bob joined")
+            },
+        ];
+        let json = export_messages(&messages, ExportFormat::Json);
+        let imported = parse_export(&json, Lang::En).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].from, "alice");
+        assert_eq!(imported[0].message, "hi");
+        assert_eq!(imported[0].id.as_deref(), Some("1"));
+        assert_eq!(imported[0].timestamp, Some(1_700_000_000_000.0));
+        assert!(parse_export("not json", Lang::En).is_err());
+        assert!(parse_export(r#"[{"from":" ","message":"x"}]"#, Lang::En).is_err());
+    }
+
+    fn numbered(range: Range<usize>) -> Vec<MessageData> {
+        range
+            .map(|i| chat_message("alice", &i.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn trim_oldest_caps_message_count() {
+        let mut messages = numbered(0..MAX_MESSAGES);
+        assert!(!trim_oldest(&mut messages));
+        assert_eq!(messages.len(), MAX_MESSAGES);
+
+        messages.extend(numbered(MAX_MESSAGES..MAX_MESSAGES + 5));
+        assert!(trim_oldest(&mut messages));
+        assert_eq!(messages.len(), MAX_MESSAGES);
+        assert_eq!(messages[0].message, "5");
+        assert_eq!(
+            messages[MAX_MESSAGES - 1].message,
+            (MAX_MESSAGES + 4).to_string()
+        );
+    }
+
+    #[test]
+    fn prepend_history_keeps_newest_messages() {
+        let mut messages = numbered(100..MAX_MESSAGES + 95);
+        assert!(prepend_history(&mut messages, numbered(0..100)));
+        assert_eq!(messages.len(), MAX_MESSAGES);
+        // Yang dibuang adalah bagian tertua dari halaman riwayat
+        assert_eq!(messages[0].message, "95");
+        assert_eq!(
+            messages[MAX_MESSAGES - 1].message,
+            (MAX_MESSAGES + 94).to_string()
+        );
+        // Sudah dalam batas, trim_oldest tidak membuang riwayat yang baru dimuat
+        assert!(!trim_oldest(&mut messages));
+
+        let mut messages = numbered(10..20);
+        assert!(!prepend_history(&mut messages, numbered(0..10)));
+        assert_eq!(messages.len(), 20);
+        assert_eq!(messages[0].message, "0");
+    }
+
+    #[test]
+    fn future_message_types_are_ignored() {
+        let frame = r#"{"messageType":"voicenote","data":"x","version":7}"#;
+        let msg: WebSocketMessage = serde_json::from_str(frame).unwrap();
+        assert!(msg.message_type == MsgTypes::Unknown);
+        assert_eq!(msg.version, 7);
+        assert!(parse_frame(frame).unwrap().is_none());
+    }
+
+    #[test]
+    fn missing_version_means_old_server() {
+        let msg = parse_frame(r#"{"messageType":"users","dataArray":["a"]}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(msg.version, 0);
+        assert_eq!(
+            WebSocketMessage::new(MsgTypes::Typing, None).version,
+            PROTOCOL_VERSION
+        );
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn typing_label_for_few_typers() {
+        assert_eq!(typing_label(&[], Lang::En), "");
+        assert_eq!(
+            typing_label(&names(&["Alice"]), Lang::En),
+            "Alice is typing"
+        );
+        assert_eq!(
+            typing_label(&names(&["Alice", "Bob"]), Lang::En),
+            "Alice and Bob are typing"
+        );
+        assert_eq!(
+            typing_label(&names(&["Alice", "Bob", "Carol"]), Lang::En),
+            "Alice, Bob, and Carol are typing"
+        );
+    }
+
+    #[test]
+    fn typing_label_for_many_typers() {
+        assert_eq!(
+            typing_label(&names(&["Alice", "Bob", "Carol", "Dave"]), Lang::En),
+            "Alice, Bob, and 2 others are typing"
+        );
+        assert_eq!(
+            typing_label(&names(&["A", "B", "C", "D", "E", "F"]), Lang::En),
+            "A, B, and 4 others are typing"
+        );
+    }
+
+    /// Feeds `delivered` through the same check as the Message frame handler.
+    fn receive(seen: &mut HashSet<String>, delivered: Vec<MessageData>) -> Vec<MessageData> {
+        delivered
+            .into_iter()
+            .filter(|m| first_delivery(seen, m))
+            .collect()
+    }
+
+    #[test]
+    fn redelivered_message_is_kept_once() {
+        let message = MessageData {
+            id: Some("1".to_string()),
+            ..chat_message("alice", "hi")
+        };
+        let again = MessageData {
+            id: Some("1".to_string()),
+            ..chat_message("alice", "hi")
+        };
+        let mut seen = HashSet::new();
+        assert_eq!(receive(&mut seen, vec![message, again]).len(), 1);
+    }
+
+    #[test]
+    fn different_ids_with_same_text_are_both_kept() {
+        let first = MessageData {
+            id: Some("1".to_string()),
+            ..chat_message("alice", "hi")
+        };
+        let second = MessageData {
+            id: Some("2".to_string()),
+            ..chat_message("alice", "hi")
+        };
+        let mut seen = HashSet::new();
+        let received = receive(&mut seen, vec![first, second]);
+        assert_eq!(received.len(), 2);
+        // Pesan tanpa id tidak bisa dibedakan, jadi tidak pernah dibuang
+        assert!(first_delivery(&mut seen, &chat_message("alice", "hi")));
+        assert!(first_delivery(&mut seen, &chat_message("alice", "hi")));
+    }
+
+    #[test]
+    fn ids_of_restored_messages_count_as_seen() {
+        let restored = vec![MessageData {
+            id: Some("1".to_string()),
+            ..chat_message("alice", "hi")
+        }];
+        let mut seen = message_ids(&restored);
+        assert!(!first_delivery(&mut seen, &restored[0]));
+    }
+}
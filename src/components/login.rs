@@ -3,37 +3,215 @@ use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::utils::avatar::{avatar_url, AVATARS};
+use crate::utils::invite::{self, ROOM_PARAM};
+use crate::utils::storage;
 use crate::Route;
 use crate::User;
 
+pub const USERNAME_KEY: &str = "yewchat_username";
+pub const AVATAR_KEY: &str = "yewchat_avatar";
+// "Remember me": kunjungan berikutnya langsung masuk ke chat
+pub const REMEMBER_KEY: &str = "yewchat_remember";
+
+pub const MAX_USERNAME_LEN: usize = 24;
+
+// Nama yang bisa dipakai untuk menyamar sebagai pesan dari sistem
+const RESERVED_NAMES: &[&str] = &["system", "server", "admin"];
+
+/// Cleans up a username for registration: strips control characters and
+/// surrounding whitespace, then rejects empty, overlong or reserved names.
+pub fn validate_username(name: &str) -> Result<String, String> {
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Please enter a username.".to_string());
+    }
+    if name.chars().count() > MAX_USERNAME_LEN {
+        return Err(format!(
+            "Usernames can be at most {} characters.",
+            MAX_USERNAME_LEN
+        ));
+    }
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+        return Err("That username is reserved, please pick another.".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// `name` with the smallest numeric suffix (from 2) that is not in `taken`,
+/// shortened so the result still fits in `MAX_USERNAME_LEN`.
+pub fn unique_username(name: &str, taken: &[String]) -> String {
+    (2..)
+        .map(|n: usize| {
+            let suffix = n.to_string();
+            let keep = MAX_USERNAME_LEN.saturating_sub(suffix.len());
+            format!("{}{}", name.chars().take(keep).collect::<String>(), suffix)
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+/// Goes to the chat, carrying over the room of an invite link.
+fn open_chat(history: &AnyHistory) {
+    // Room dari link undangan ikut dibawa ke /chat
+    match invite::room_from_location() {
+        Some(room) => {
+            if let Err(e) = history.push_with_query(Route::Chat, [(ROOM_PARAM, room)]) {
+                log::debug!("error building chat url: {:?}", e);
+                history.push(Route::Chat);
+            }
+        }
+        None => history.push(Route::Chat),
+    }
+}
+
 #[function_component(Login)]
 pub fn login() -> Html {
-    let username = use_state(|| String::new());
     let user = use_context::<User>().expect("No context found.");
+    let username = use_state(|| user.username.borrow().clone());
+    let avatar = use_state(|| {
+        user.avatar
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| AVATARS[0].to_string())
+    });
+    let error = use_state(|| None::<String>);
+    let remember = use_state(|| storage::load_bool(REMEMBER_KEY).unwrap_or(false));
+    let history = use_history().expect("Login rendered outside a router.");
+
+    {
+        // Nama yang diingat dan masih valid tidak perlu login ulang
+        let history = history.clone();
+        let auto_join = *remember && validate_username(&user.username.borrow()).is_ok();
+        use_effect_with_deps(
+            move |_| {
+                if auto_join {
+                    open_chat(&history);
+                }
+                || ()
+            },
+            (),
+        );
+    }
 
     let oninput = {
         let current_username = username.clone();
+        let error = error.clone();
 
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
             current_username.set(input.value());
+            error.set(None);
         })
     };
 
-    let onclick = {
+    let onremember = {
+        let remember = remember.clone();
+        Callback::from(move |_| remember.set(!*remember))
+    };
+
+    let onsubmit = {
         let username = username.clone();
+        let avatar = avatar.clone();
+        let remember = remember.clone();
+        let error = error.clone();
         let user = user.clone();
-        Callback::from(move |_| *user.username.borrow_mut() = (*username).clone())
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let name = match validate_username(&username) {
+                Ok(name) => name,
+                Err(message) => {
+                    error.set(Some(message));
+                    return;
+                }
+            };
+            // Disimpan agar refresh di /chat tidak kembali ke layar ini
+            storage::save_string(USERNAME_KEY, &name);
+            storage::save_string(AVATAR_KEY, &avatar);
+            storage::save_bool(REMEMBER_KEY, *remember);
+            *user.username.borrow_mut() = name;
+            *user.avatar.borrow_mut() = Some((*avatar).clone());
+            open_chat(&history);
+        })
     };
 
     html! {
        <div class="bg-gray-800 flex w-screen">
             <div class="container mx-auto flex flex-col justify-center items-center">
-                <form class="m-4 flex">
-                    <input {oninput} class="rounded-l-lg p-4 border-t mr-0 border-b border-l text-gray-800 border-gray-200 bg-white" placeholder="Username" />
-                    <Link<Route> to={Route::Chat}> <button {onclick} disabled={username.len()<1} class="px-8 rounded-r-lg bg-violet-600	  text-white font-bold p-4 uppercase border-violet-600 border-t border-b border-r" >{"Go Chatting!"}</button></Link<Route>>
+                <form {onsubmit} class="m-4 flex flex-col items-center">
+                    <div class="grid grid-cols-4 gap-3 mb-6">
+                        {
+                            AVATARS.iter().map(|name| {
+                                let selected = *avatar == *name;
+                                let onclick = {
+                                    let avatar = avatar.clone();
+                                    Callback::from(move |_| avatar.set(name.to_string()))
+                                };
+                                html! {
+                                    <button
+                                        type="button"
+                                        {onclick}
+                                        title={name.to_string()}
+                                        class={format!("w-16 h-16 rounded-full bg-gray-700 ring-4 {}", if selected { "ring-violet-600" } else { "ring-transparent hover:ring-gray-500" })}
+                                    >
+                                        <img class="w-16 h-16 rounded-full" src={avatar_url(name)} alt={name.to_string()} />
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                    <div class="flex">
+                        <input {oninput} value={(*username).clone()} class="rounded-l-lg p-4 border-t mr-0 border-b border-l text-gray-800 border-gray-200 bg-white" placeholder="Username" />
+                        <button type="submit" disabled={username.trim().is_empty()} class="px-8 rounded-r-lg bg-violet-600	  text-white font-bold p-4 uppercase border-violet-600 border-t border-b border-r" >{"Go Chatting!"}</button>
+                    </div>
+                    <label class="mt-3 flex items-center text-sm text-gray-300 cursor-pointer">
+                        <input type="checkbox" checked={*remember} onchange={onremember} class="mr-2" />
+                        {"Remember me"}
+                    </label>
+                    if let Some(message) = (*error).clone() {
+                        <p class="mt-3 text-sm text-red-400">{message}</p>
+                    }
                 </form>
             </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unicode_names() {
+        assert_eq!(validate_username("Ünïcødé"), Ok("Ünïcødé".to_string()));
+        assert_eq!(validate_username("  日本語  "), Ok("日本語".to_string()));
+    }
+
+    #[test]
+    fn accepts_emoji_names() {
+        assert_eq!(validate_username("🦀🦀"), Ok("🦀🦀".to_string()));
+    }
+
+    #[test]
+    fn strips_control_characters() {
+        assert_eq!(validate_username("ali\u{0}ce\n"), Ok("alice".to_string()));
+        assert!(validate_username("\t\n ").is_err());
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        assert!(validate_username("system").is_err());
+        assert!(validate_username(" Admin ").is_err());
+        assert!(validate_username("SERVER").is_err());
+        assert!(validate_username("administrator").is_ok());
+    }
+
+    #[test]
+    fn caps_length_in_characters() {
+        let max = "é".repeat(MAX_USERNAME_LEN);
+        assert_eq!(validate_username(&max), Ok(max.clone()));
+        assert!(validate_username(&format!("{}é", max)).is_err());
+        assert!(validate_username("").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Small notifications stacked in the bottom-right corner that dismiss
+//! themselves after a few seconds. The owner keeps the `Vec<Toast>` and
+//! removes a toast when [`Toasts`] reports it through `on_dismiss`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+use crate::i18n::{t, Lang};
+
+// Toast lama dibuang agar tumpukan tidak menutupi layar
+pub const MAX_TOASTS: usize = 4;
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    fn icon(self) -> &'static str {
+        match self {
+            Severity::Error => "⛔",
+            Severity::Warning => "⚠️",
+            Severity::Info => "ℹ️",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Severity::Error => "bg-red-600",
+            Severity::Warning => "bg-yellow-500",
+            Severity::Info => "bg-gray-800",
+        }
+    }
+
+    // Error dibiarkan lebih lama karena biasanya perlu dibaca
+    fn duration_ms(self) -> u32 {
+        match self {
+            Severity::Error => 8000,
+            Severity::Warning => 6000,
+            Severity::Info => 4000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub id: u32,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Toast {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            severity,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(Severity::Info, message)
+    }
+}
+
+/// Adds `toast` to `toasts`, dropping the oldest ones beyond `MAX_TOASTS`.
+pub fn push_toast(toasts: &mut Vec<Toast>, toast: Toast) {
+    toasts.push(toast);
+    let overflow = toasts.len().saturating_sub(MAX_TOASTS);
+    toasts.drain(..overflow);
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastsProps {
+    pub toasts: Vec<Toast>,
+    pub lang: Lang,
+    pub on_dismiss: Callback<u32>,
+}
+
+#[function_component(Toasts)]
+pub fn toasts(props: &ToastsProps) -> Html {
+    if props.toasts.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="fixed bottom-4 right-4 z-50 flex flex-col items-end space-y-2" role="region" aria-label={t("notifications", props.lang)}>
+            {
+                props.toasts.iter().map(|toast| html! {
+                    <ToastItem key={toast.id} toast={toast.clone()} lang={props.lang} on_dismiss={props.on_dismiss.clone()} />
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ToastItemProps {
+    toast: Toast,
+    lang: Lang,
+    on_dismiss: Callback<u32>,
+}
+
+#[function_component(ToastItem)]
+fn toast_item(props: &ToastItemProps) -> Html {
+    let Toast {
+        id,
+        severity,
+        message,
+    } = props.toast.clone();
+
+    {
+        // Timer ikut batal saat toast ditutup lebih dulu
+        let on_dismiss = props.on_dismiss.clone();
+        use_effect_with_deps(
+            move |_| {
+                let timeout = Timeout::new(severity.duration_ms(), move || on_dismiss.emit(id));
+                move || drop(timeout)
+            },
+            id,
+        );
+    }
+    let dismiss = {
+        let on_dismiss = props.on_dismiss.clone();
+        Callback::from(move |_| on_dismiss.emit(id))
+    };
+    // Error dibacakan segera oleh screen reader, sisanya menunggu giliran
+    let role = if severity == Severity::Error {
+        "alert"
+    } else {
+        "status"
+    };
+
+    html! {
+        <div {role} class={format!("flex items-start max-w-sm px-4 py-3 space-x-3 text-sm text-white rounded-lg shadow-lg {}", severity.class())}>
+            <span aria-hidden="true">{severity.icon()}</span>
+            <span class="flex-1 break-words">{message}</span>
+            <button
+                type="button"
+                onclick={dismiss}
+                class="hover:opacity-75"
+                title={t("dismiss", props.lang)}
+                aria-label={t("dismiss", props.lang)}
+            >
+                {"✕"}
+            </button>
+        </div>
+    }
+}
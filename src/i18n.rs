@@ -0,0 +1,663 @@
+//! UI strings for every supported language. Text is looked up by key with
+//! [`t`]; a key missing from a translation falls back to English. Values may
+//! contain `{name}` or `{count}` placeholders that the caller fills in.
+
+pub const LANG_KEY: &str = "yewchat_lang";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    En,
+    Id,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Id];
+
+    // Kode bahasa BCP 47, juga nilai yang disimpan di localStorage
+    pub fn key(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Id => "id",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.key() == key)
+    }
+
+    // Ditulis dalam bahasanya sendiri agar tetap terbaca oleh penuturnya
+    pub fn label(self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::Id => "Bahasa Indonesia",
+        }
+    }
+
+    /// Picks the browser's preferred language (e.g. "id-ID" → Indonesian),
+    /// falling back to English.
+    pub fn from_browser() -> Self {
+        web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .and_then(|tag| {
+                let primary = tag.split('-').next().unwrap_or_default().to_lowercase();
+                Self::from_key(&primary)
+            })
+            .unwrap_or(Lang::En)
+    }
+}
+
+const EN: &[(&str, &str)] = &[
+    ("rooms", "Rooms"),
+    ("copy_invite_link", "🔗 Invite"),
+    ("copy_invite_link_hint", "Copy a link that opens this room"),
+    ("link_copied", "Link copied!"),
+    ("online_users", "Online Users"),
+    ("recently_offline", "Recently offline"),
+    ("last_seen", "last seen {time}"),
+    ("filter_users", "Filter users"),
+    ("filter_users_placeholder", "Filter users..."),
+    ("sort_users", "Sort users"),
+    ("users_load_failed", "Couldn't load users"),
+    ("no_users", "No users online"),
+    ("no_matching_users", "No matching users"),
+    ("you", "You"),
+    ("you_suffix", "(you)"),
+    ("muted_suffix", "(muted)"),
+    ("mute_user", "Mute user"),
+    ("unmute_user", "Unmute user"),
+    ("unmute", "Unmute"),
+    ("muted_message_hidden", "🔇 Message from {name} hidden"),
+    ("message_privately", "Message {name} privately"),
+    ("joining", "⏳ Joining…"),
+    ("status_online", "🟢 Online"),
+    ("status_away", "🟡 Away"),
+    ("role_admin", "Admin"),
+    ("role_mod", "Moderator"),
+    ("unread_private", "Unread private messages"),
+    ("unread_private_count", "{count} unread private messages"),
+    ("connected", "Connected"),
+    ("reconnecting", "Reconnecting…"),
+    ("disconnected", "Disconnected"),
+    (
+        "connection_lost",
+        "Connection lost. Messages can't be sent until you reconnect.",
+    ),
+    ("reconnect_now", "Reconnect now"),
+    ("dismiss", "Dismiss"),
+    ("message_count", "{count} messages"),
+    ("search_messages", "Search messages"),
+    ("search_messages_placeholder", "Search messages..."),
+    ("search_all", "Search all"),
+    ("search_all_hint", "Search the full history on the server"),
+    ("search_results", "Search results"),
+    ("searching", "Searching…"),
+    ("no_search_results", "No messages found"),
+    ("jump_to_message", "Jump to message"),
+    (
+        "search_result_not_loaded",
+        "This message isn't loaded in the chat",
+    ),
+    ("clear_chat", "Clear chat"),
+    ("confirm_clear", "Confirm?"),
+    ("confirm_clear_hint", "Click again to clear all messages"),
+    ("settings", "Settings"),
+    ("toggle_sidebar", "Rooms and users"),
+    ("messages", "Messages"),
+    ("chat_load_failed", "Couldn't load the chat"),
+    (
+        "chat_load_retry",
+        "Check your connection, we'll keep trying.",
+    ),
+    ("no_messages", "No messages yet"),
+    (
+        "dm_private_hint",
+        "Only you and {name} can see this conversation.",
+    ),
+    ("start_conversation", "Start a conversation!"),
+    ("no_matching_messages", "No matching messages"),
+    ("loading_older", "Loading older messages…"),
+    ("scroll_to_bottom", "Scroll to bottom"),
+    (
+        "scroll_to_bottom_new",
+        "Scroll to bottom, {count} new messages",
+    ),
+    ("new_count", "↓ {count} new"),
+    ("new_messages", "New messages"),
+    ("replying_to", "Replying to {name}: "),
+    ("cancel_reply", "Cancel reply"),
+    (
+        "editing_message",
+        "Editing message · Enter to save, Esc to cancel",
+    ),
+    ("cancel_edit", "Cancel edit"),
+    (
+        "edited_message_deleted",
+        "The message you were editing was deleted",
+    ),
+    ("message_input", "Message"),
+    ("message_placeholder", "Type your message..."),
+    ("clear_input", "Clear message"),
+    ("slow_down", "Slow down! You're sending messages too fast."),
+    ("insert_emoji", "Insert emoji"),
+    ("send_gif", "Send a GIF"),
+    ("send", "Send"),
+    ("send_message", "Send message"),
+    ("language", "Language"),
+    ("notifications", "Notifications"),
+    (
+        "toast_send_failed",
+        "Your message couldn't be sent. Tap ❗ to retry.",
+    ),
+    (
+        "toast_offline",
+        "You're offline. Your message will stay in the box.",
+    ),
+    ("toast_connection_lost", "Connection lost, reconnecting…"),
+    ("toast_reconnect_failed", "Couldn't reach the chat server."),
+    ("toast_reconnected", "Reconnected."),
+    (
+        "reaction_notification",
+        "{name} reacted {emoji} to your message",
+    ),
+    (
+        "toast_name_taken",
+        "{name} is already in use, you joined as {new}.",
+    ),
+    (
+        "toast_malformed_frame",
+        "Received a message the app couldn't read.",
+    ),
+    ("toast_export_failed", "Couldn't export the chat."),
+    ("toast_copy_failed", "Couldn't copy to the clipboard."),
+    (
+        "toast_drop_not_image",
+        "Only PNG, JPEG, GIF or WebP images can be shared.",
+    ),
+    (
+        "toast_drop_too_large",
+        "That image is too large, the limit is 256 KB.",
+    ),
+    ("toast_drop_read_failed", "Couldn't read the dropped file."),
+    ("drop_image", "Drop an image to share it"),
+    ("moderate", "Moderate"),
+    ("moderate_user", "Moderate {name}"),
+    ("kick", "Kick"),
+    ("ban", "Ban"),
+    ("confirm_kick", "Kick {name}?"),
+    ("confirm_ban", "Ban {name}?"),
+    ("kick_hint", "They'll be disconnected but can join again."),
+    ("ban_hint", "They'll be disconnected and can't join again."),
+    ("kick_notice", "🚫 {name} was kicked"),
+    ("ban_notice", "🚫 {name} was banned"),
+    ("cancel", "Cancel"),
+    ("removed_kick", "You were removed from the chat"),
+    ("removed_ban", "You were banned from the chat"),
+    ("removed_hint", "A moderator disconnected you."),
+    ("back_to_login", "Back to login"),
+    ("react_with", "React with {emoji}"),
+    ("reply", "Reply"),
+    ("copy_message", "Copy message"),
+    ("copied", "Copied!"),
+    ("mute_name", "Mute {name}"),
+    ("delete_message", "Delete message"),
+    ("private_badge", "🔒 Private"),
+    ("private_message", "Private message"),
+    ("edited", "(edited)"),
+    ("image", "Image"),
+    ("delivery_sending", "Sending"),
+    ("delivery_sent", "Sent"),
+    ("delivery_failed", "Failed to send"),
+    ("show_more", "Show more"),
+    ("show_less", "Show less"),
+    ("seen_by", "Seen by {names}"),
+    ("original_not_loaded", "↩ Original message isn't loaded"),
+    ("just_now", "just now"),
+    ("minutes_ago", "{count}m ago"),
+    ("hours_ago", "{count}h ago"),
+    ("days_ago", "{count}d ago"),
+    ("today", "Today"),
+    ("yesterday", "Yesterday"),
+    ("date_short", "{month} {day}"),
+    ("date_long", "{month} {day}, {year}"),
+    ("month_january", "January"),
+    ("month_february", "February"),
+    ("month_march", "March"),
+    ("month_april", "April"),
+    ("month_may", "May"),
+    ("month_june", "June"),
+    ("month_july", "July"),
+    ("month_august", "August"),
+    ("month_september", "September"),
+    ("month_october", "October"),
+    ("month_november", "November"),
+    ("month_december", "December"),
+    ("typing_one", "{name} is typing"),
+    ("typing_two", "{a} and {b} are typing"),
+    ("typing_three", "{a}, {b}, and {c} are typing"),
+    ("typing_many", "{a}, {b}, and {count} others are typing"),
+    ("user_joined", "🟢 {name} joined"),
+    ("user_left", "⚪ {name} left"),
+    ("unknown_command", "Unknown command: /{name}"),
+    ("cleared_count", "Cleared {count} messages"),
+    ("undo", "Undo"),
+    ("close", "Close"),
+    ("close_settings", "Close settings"),
+    ("font_size", "Font size"),
+    ("font_small", "Small"),
+    ("font_medium", "Medium"),
+    ("font_large", "Large"),
+    ("message_density", "Message density"),
+    ("density_comfortable", "Comfortable"),
+    ("density_compact", "Compact"),
+    ("theme", "Theme"),
+    ("theme_light", "☀️ Light"),
+    ("theme_dark", "🌙 Dark"),
+    ("theme_high_contrast", "◐ High contrast"),
+    ("on", "On"),
+    ("off", "Off"),
+    ("notification_sound", "Notification sound"),
+    ("word_filter", "Word filter"),
+    ("word_filter_hint", "Mask common swear words in messages"),
+    ("reaction_alerts", "Reaction alerts"),
+    (
+        "reaction_alerts_hint",
+        "Count reactions to your messages as unread and notify you while the tab is in the background",
+    ),
+    ("show_images", "Show images"),
+    ("show_images_hint", "Load images in messages and link previews automatically"),
+    ("instant_send", "Instant send"),
+    (
+        "instant_send_hint",
+        "Show your messages before the server confirms them. Turn off to debug latency or ordering.",
+    ),
+    ("batch_incoming", "Batch incoming"),
+    (
+        "batch_incoming_hint",
+        "Apply incoming messages once per frame. Keeps busy rooms smooth at the cost of a few milliseconds of delay.",
+    ),
+    ("chat_history", "Chat history"),
+    ("export_format", "Export format"),
+    ("export_json", "JSON"),
+    ("export_text", "Text"),
+    ("export", "⬇️ Export"),
+    ("import_mode", "Import mode"),
+    ("import_merge", "Merge"),
+    ("import_replace", "Replace"),
+    ("import", "⬆️ Import"),
+    ("imported_count", "📥 Imported {count} messages"),
+    (
+        "import_invalid",
+        "This file isn't a valid chat export ({error})",
+    ),
+    ("import_no_sender", "message without sender"),
+    ("import_read_failed", "Couldn't read the file ({error})"),
+    ("avatar_color", "Avatar color"),
+    ("reset_color", "Reset color"),
+    ("choose_avatar_color", "Choose avatar color"),
+    ("sign_out", "Sign out"),
+    ("sort_arrival", "Recently joined"),
+    ("sort_name", "Name (A–Z)"),
+    ("sort_status", "Status"),
+    ("emoji_picker", "Emoji picker"),
+    ("insert_named", "Insert {emoji}"),
+    ("gif_picker", "GIF picker"),
+    ("search_gifs", "Search GIFs"),
+    ("search_gifs_placeholder", "Search GIFs..."),
+    ("gif_search_hint", "Type to search for GIFs"),
+    ("no_gifs", "No GIFs found"),
+    ("powered_by_tenor", "Powered by Tenor"),
+    ("pinned", "Pinned"),
+    ("pinned_messages", "Pinned messages"),
+    ("pinned_count", "📌 Pinned ({count})"),
+    ("show_all", "Show all"),
+    ("pin_message", "Pin message"),
+    ("unpin_message", "Unpin message"),
+    ("message_actions", "Message actions"),
+    ("menu_reply", "↩️ Reply"),
+    ("menu_copy", "📋 Copy"),
+    ("menu_pin", "📌 Pin"),
+    ("menu_unpin", "📌 Unpin"),
+    ("menu_delete", "🗑️ Delete"),
+];
+
+const ID: &[(&str, &str)] = &[
+    ("rooms", "Room"),
+    ("copy_invite_link", "🔗 Undang"),
+    ("copy_invite_link_hint", "Salin link yang membuka room ini"),
+    ("link_copied", "Link disalin!"),
+    ("online_users", "Pengguna Online"),
+    ("recently_offline", "Baru saja offline"),
+    ("last_seen", "terakhir terlihat {time}"),
+    ("filter_users", "Saring pengguna"),
+    ("filter_users_placeholder", "Saring pengguna..."),
+    ("sort_users", "Urutkan pengguna"),
+    ("users_load_failed", "Gagal memuat pengguna"),
+    ("no_users", "Tidak ada pengguna online"),
+    ("no_matching_users", "Tidak ada pengguna yang cocok"),
+    ("you", "Anda"),
+    ("you_suffix", "(Anda)"),
+    ("muted_suffix", "(dibisukan)"),
+    ("mute_user", "Bisukan pengguna"),
+    ("unmute_user", "Batal bisukan"),
+    ("unmute", "Batal bisukan"),
+    ("muted_message_hidden", "🔇 Pesan dari {name} disembunyikan"),
+    ("message_privately", "Kirim pesan pribadi ke {name}"),
+    ("joining", "⏳ Bergabung…"),
+    ("status_online", "🟢 Online"),
+    ("status_away", "🟡 Sedang pergi"),
+    ("role_admin", "Admin"),
+    ("role_mod", "Moderator"),
+    ("unread_private", "Pesan pribadi belum dibaca"),
+    ("unread_private_count", "{count} pesan pribadi belum dibaca"),
+    ("connected", "Terhubung"),
+    ("reconnecting", "Menyambung ulang…"),
+    ("disconnected", "Terputus"),
+    (
+        "connection_lost",
+        "Koneksi terputus. Pesan tidak bisa dikirim sebelum tersambung lagi.",
+    ),
+    ("reconnect_now", "Sambungkan sekarang"),
+    ("dismiss", "Tutup"),
+    ("message_count", "{count} pesan"),
+    ("search_messages", "Cari pesan"),
+    ("search_messages_placeholder", "Cari pesan..."),
+    ("search_all", "Cari semua"),
+    ("search_all_hint", "Cari di seluruh riwayat di server"),
+    ("search_results", "Hasil pencarian"),
+    ("searching", "Mencari…"),
+    ("no_search_results", "Tidak ada pesan yang ditemukan"),
+    ("jump_to_message", "Lompat ke pesan"),
+    ("search_result_not_loaded", "Pesan ini belum dimuat di chat"),
+    ("clear_chat", "Hapus chat"),
+    ("confirm_clear", "Yakin?"),
+    (
+        "confirm_clear_hint",
+        "Klik lagi untuk menghapus semua pesan",
+    ),
+    ("settings", "Pengaturan"),
+    ("toggle_sidebar", "Room dan pengguna"),
+    ("messages", "Pesan"),
+    ("chat_load_failed", "Gagal memuat chat"),
+    (
+        "chat_load_retry",
+        "Periksa koneksi Anda, kami akan terus mencoba.",
+    ),
+    ("no_messages", "Belum ada pesan"),
+    (
+        "dm_private_hint",
+        "Hanya Anda dan {name} yang bisa melihat percakapan ini.",
+    ),
+    ("start_conversation", "Mulai percakapan!"),
+    ("no_matching_messages", "Tidak ada pesan yang cocok"),
+    ("loading_older", "Memuat pesan lama…"),
+    ("scroll_to_bottom", "Gulir ke bawah"),
+    ("scroll_to_bottom_new", "Gulir ke bawah, {count} pesan baru"),
+    ("new_count", "↓ {count} baru"),
+    ("new_messages", "Pesan baru"),
+    ("replying_to", "Membalas {name}: "),
+    ("cancel_reply", "Batal membalas"),
+    (
+        "editing_message",
+        "Mengedit pesan · Enter untuk simpan, Esc untuk batal",
+    ),
+    ("cancel_edit", "Batal mengedit"),
+    (
+        "edited_message_deleted",
+        "Pesan yang Anda edit sudah dihapus",
+    ),
+    ("message_input", "Pesan"),
+    ("message_placeholder", "Ketik pesan Anda..."),
+    ("clear_input", "Hapus isi pesan"),
+    (
+        "slow_down",
+        "Pelan-pelan! Anda mengirim pesan terlalu cepat.",
+    ),
+    ("insert_emoji", "Sisipkan emoji"),
+    ("send_gif", "Kirim GIF"),
+    ("send", "Kirim"),
+    ("send_message", "Kirim pesan"),
+    ("language", "Bahasa"),
+    ("notifications", "Notifikasi"),
+    (
+        "toast_send_failed",
+        "Pesan Anda gagal terkirim. Ketuk ❗ untuk mencoba lagi.",
+    ),
+    (
+        "toast_offline",
+        "Anda sedang offline. Pesan tetap tersimpan di kotak input.",
+    ),
+    (
+        "toast_connection_lost",
+        "Koneksi terputus, menyambung ulang…",
+    ),
+    (
+        "toast_reconnect_failed",
+        "Tidak bisa menghubungi server chat.",
+    ),
+    ("toast_reconnected", "Tersambung kembali."),
+    (
+        "reaction_notification",
+        "{name} memberi reaksi {emoji} ke pesanmu",
+    ),
+    (
+        "toast_name_taken",
+        "{name} sudah dipakai, kamu bergabung sebagai {new}.",
+    ),
+    (
+        "toast_malformed_frame",
+        "Menerima pesan yang tidak bisa dibaca aplikasi.",
+    ),
+    ("toast_export_failed", "Gagal mengekspor chat."),
+    ("toast_copy_failed", "Gagal menyalin ke clipboard."),
+    (
+        "toast_drop_not_image",
+        "Hanya gambar PNG, JPEG, GIF, atau WebP yang bisa dibagikan.",
+    ),
+    (
+        "toast_drop_too_large",
+        "Gambar terlalu besar, batasnya 256 KB.",
+    ),
+    ("toast_drop_read_failed", "Gagal membaca file yang di-drop."),
+    ("drop_image", "Lepaskan gambar untuk membagikannya"),
+    ("moderate", "Moderasi"),
+    ("moderate_user", "Moderasi {name}"),
+    ("kick", "Keluarkan"),
+    ("ban", "Blokir"),
+    ("confirm_kick", "Keluarkan {name}?"),
+    ("confirm_ban", "Blokir {name}?"),
+    (
+        "kick_hint",
+        "Koneksinya akan diputus, tapi bisa bergabung lagi.",
+    ),
+    (
+        "ban_hint",
+        "Koneksinya akan diputus dan tidak bisa bergabung lagi.",
+    ),
+    ("kick_notice", "🚫 {name} dikeluarkan"),
+    ("ban_notice", "🚫 {name} diblokir"),
+    ("cancel", "Batal"),
+    ("removed_kick", "Anda dikeluarkan dari chat"),
+    ("removed_ban", "Anda diblokir dari chat"),
+    ("removed_hint", "Seorang moderator memutus koneksi Anda."),
+    ("back_to_login", "Kembali ke login"),
+    ("react_with", "Beri reaksi {emoji}"),
+    ("reply", "Balas"),
+    ("copy_message", "Salin pesan"),
+    ("copied", "Disalin!"),
+    ("mute_name", "Bisukan {name}"),
+    ("delete_message", "Hapus pesan"),
+    ("private_badge", "🔒 Pribadi"),
+    ("private_message", "Pesan pribadi"),
+    ("edited", "(diedit)"),
+    ("image", "Gambar"),
+    ("delivery_sending", "Mengirim"),
+    ("delivery_sent", "Terkirim"),
+    ("delivery_failed", "Gagal terkirim"),
+    ("show_more", "Tampilkan lebih banyak"),
+    ("show_less", "Tampilkan lebih sedikit"),
+    ("seen_by", "Dilihat oleh {names}"),
+    ("original_not_loaded", "↩ Pesan asli belum dimuat"),
+    ("just_now", "baru saja"),
+    ("minutes_ago", "{count} mnt lalu"),
+    ("hours_ago", "{count} jam lalu"),
+    ("days_ago", "{count} hr lalu"),
+    ("today", "Hari ini"),
+    ("yesterday", "Kemarin"),
+    ("date_short", "{day} {month}"),
+    ("date_long", "{day} {month} {year}"),
+    ("month_january", "Januari"),
+    ("month_february", "Februari"),
+    ("month_march", "Maret"),
+    ("month_april", "April"),
+    ("month_may", "Mei"),
+    ("month_june", "Juni"),
+    ("month_july", "Juli"),
+    ("month_august", "Agustus"),
+    ("month_september", "September"),
+    ("month_october", "Oktober"),
+    ("month_november", "November"),
+    ("month_december", "Desember"),
+    ("typing_one", "{name} sedang mengetik"),
+    ("typing_two", "{a} dan {b} sedang mengetik"),
+    ("typing_three", "{a}, {b}, dan {c} sedang mengetik"),
+    (
+        "typing_many",
+        "{a}, {b}, dan {count} lainnya sedang mengetik",
+    ),
+    ("user_joined", "🟢 {name} bergabung"),
+    ("user_left", "⚪ {name} keluar"),
+    ("unknown_command", "Perintah tidak dikenal: /{name}"),
+    ("cleared_count", "{count} pesan dihapus"),
+    ("undo", "Urungkan"),
+    ("close", "Tutup"),
+    ("close_settings", "Tutup pengaturan"),
+    ("font_size", "Ukuran huruf"),
+    ("font_small", "Kecil"),
+    ("font_medium", "Sedang"),
+    ("font_large", "Besar"),
+    ("message_density", "Kerapatan pesan"),
+    ("density_comfortable", "Lega"),
+    ("density_compact", "Rapat"),
+    ("theme", "Tema"),
+    ("theme_light", "☀️ Terang"),
+    ("theme_dark", "🌙 Gelap"),
+    ("theme_high_contrast", "◐ Kontras tinggi"),
+    ("on", "Nyala"),
+    ("off", "Mati"),
+    ("notification_sound", "Suara notifikasi"),
+    ("word_filter", "Filter kata"),
+    (
+        "word_filter_hint",
+        "Sensor kata-kata kasar yang umum di pesan",
+    ),
+    ("reaction_alerts", "Notifikasi reaksi"),
+    (
+        "reaction_alerts_hint",
+        "Hitung reaksi ke pesan Anda sebagai belum dibaca dan beri notifikasi saat tab di latar belakang",
+    ),
+    ("show_images", "Tampilkan gambar"),
+    ("show_images_hint", "Muat gambar di pesan dan preview link secara otomatis"),
+    ("instant_send", "Kirim instan"),
+    (
+        "instant_send_hint",
+        "Tampilkan pesan Anda sebelum dikonfirmasi server. Matikan untuk men-debug latensi atau urutan pesan.",
+    ),
+    ("batch_incoming", "Kelompokkan pesan masuk"),
+    (
+        "batch_incoming_hint",
+        "Terapkan pesan masuk sekali per frame. Room yang ramai tetap lancar dengan jeda beberapa milidetik.",
+    ),
+    ("chat_history", "Riwayat chat"),
+    ("export_format", "Format ekspor"),
+    ("export_json", "JSON"),
+    ("export_text", "Teks"),
+    ("export", "⬇️ Ekspor"),
+    ("import_mode", "Mode impor"),
+    ("import_merge", "Gabungkan"),
+    ("import_replace", "Ganti"),
+    ("import", "⬆️ Impor"),
+    ("imported_count", "📥 {count} pesan diimpor"),
+    (
+        "import_invalid",
+        "File ini bukan ekspor chat yang valid ({error})",
+    ),
+    ("import_no_sender", "pesan tanpa pengirim"),
+    ("import_read_failed", "Gagal membaca file ({error})"),
+    ("avatar_color", "Warna avatar"),
+    ("reset_color", "Reset warna"),
+    ("choose_avatar_color", "Pilih warna avatar"),
+    ("sign_out", "Keluar"),
+    ("sort_arrival", "Baru bergabung"),
+    ("sort_name", "Nama (A–Z)"),
+    ("sort_status", "Status"),
+    ("emoji_picker", "Pemilih emoji"),
+    ("insert_named", "Sisipkan {emoji}"),
+    ("gif_picker", "Pemilih GIF"),
+    ("search_gifs", "Cari GIF"),
+    ("search_gifs_placeholder", "Cari GIF..."),
+    ("gif_search_hint", "Ketik untuk mencari GIF"),
+    ("no_gifs", "GIF tidak ditemukan"),
+    ("powered_by_tenor", "Didukung oleh Tenor"),
+    ("pinned", "Disematkan"),
+    ("pinned_messages", "Pesan yang disematkan"),
+    ("pinned_count", "📌 Disematkan ({count})"),
+    ("show_all", "Tampilkan semua"),
+    ("pin_message", "Sematkan pesan"),
+    ("unpin_message", "Lepas sematan"),
+    ("message_actions", "Aksi pesan"),
+    ("menu_reply", "↩️ Balas"),
+    ("menu_copy", "📋 Salin"),
+    ("menu_pin", "📌 Sematkan"),
+    ("menu_unpin", "📌 Lepas sematan"),
+    ("menu_delete", "🗑️ Hapus"),
+];
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/// Returns the text for `key` in `lang`, or the English text if it hasn't
+/// been translated. Unknown keys are logged and render as an empty string.
+pub fn t(key: &str, lang: Lang) -> &'static str {
+    let table = match lang {
+        Lang::En => EN,
+        Lang::Id => ID,
+    };
+    lookup(table, key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or_else(|| {
+            log::warn!("missing UI string: {}", key);
+            ""
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_unique() {
+        for table in [EN, ID] {
+            for (i, (key, _)) in table.iter().enumerate() {
+                assert!(
+                    !table[..i].iter().any(|(k, _)| k == key),
+                    "duplicate key {}",
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn translations_have_english_text() {
+        for (key, _) in ID {
+            assert!(lookup(EN, key).is_some(), "{} has no English text", key);
+        }
+    }
+
+    #[test]
+    fn looks_up_by_language() {
+        assert_eq!(t("undo", Lang::En), "Undo");
+        assert_eq!(t("undo", Lang::Id), "Urungkan");
+    }
+}
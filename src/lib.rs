@@ -1,7 +1,12 @@
 #![recursion_limit = "512"]
+// The `html!` macro in yew 0.19 expands component props into statements that
+// clippy misreads as no-op expressions.
+#![allow(clippy::unnecessary_operation, clippy::let_unit_value)]
 
 mod components;
+mod i18n;
 mod services;
+mod utils;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -12,7 +17,8 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 
 use components::chat::Chat;
-use components::login::Login;
+use components::login::{validate_username, Login, AVATAR_KEY, USERNAME_KEY};
+use utils::storage;
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
 // allocator.
@@ -38,30 +44,45 @@ pub type User = Rc<UserInner>;
 #[derive(Debug, PartialEq)]
 pub struct UserInner {
     pub username: RefCell<String>,
+    // Warna avatar pilihan user ("#rrggbb"), None berarti diturunkan dari nama
+    pub color: RefCell<Option<String>>,
+    // Nama avatar (seed gambar), None berarti memakai username
+    pub avatar: RefCell<Option<String>>,
 }
 
 #[function_component(Main)]
 fn main() -> Html {
+    // Identitas dari kunjungan sebelumnya, kosong jika belum pernah login
     let ctx = use_state(|| {
         Rc::new(UserInner {
-            username: RefCell::new("initial".into()),
+            username: RefCell::new(storage::load_string(USERNAME_KEY).unwrap_or_default()),
+            color: RefCell::new(None),
+            avatar: RefCell::new(storage::load_string(AVATAR_KEY)),
         })
     });
+    let render = {
+        let user = (*ctx).clone();
+        Switch::render(move |route| switch(route, &user))
+    };
 
     html! {
         <ContextProvider<User> context={(*ctx).clone()}>
             <BrowserRouter>
                 <div class="flex w-screen h-screen">
-                    <Switch<Route> render={Switch::render(switch)}/>
+                    <Switch<Route> {render}/>
                 </div>
             </BrowserRouter>
         </ContextProvider<User>>
     }
 }
 
-fn switch(selected_route: &Route) -> Html {
+fn switch(selected_route: &Route, user: &User) -> Html {
     match selected_route {
         Route::Login => html! {<Login />},
+        // Nama tersimpan bisa saja diubah manual, jadi divalidasi lagi
+        Route::Chat if validate_username(&user.username.borrow()).is_err() => {
+            html! {<Redirect<Route> to={Route::Login}/>}
+        }
         Route::Chat => html! {<Chat/>},
         Route::NotFound => html! {<h1>{"404 baby"}</h1>},
     }
@@ -0,0 +1,26 @@
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+// `navigator.clipboard` hanya ada di konteks aman (https/localhost)
+fn clipboard() -> Option<JsValue> {
+    let window: JsValue = web_sys::window()?.into();
+    let navigator = Reflect::get(&window, &JsValue::from_str("navigator")).ok()?;
+    let clipboard = Reflect::get(&navigator, &JsValue::from_str("clipboard")).ok()?;
+    (!clipboard.is_undefined() && !clipboard.is_null()).then_some(clipboard)
+}
+
+pub fn is_supported() -> bool {
+    clipboard().is_some()
+}
+
+/// Copies `text` to the clipboard through the async Clipboard API.
+pub async fn write_text(text: &str) -> Result<(), JsValue> {
+    let clipboard = clipboard().ok_or_else(|| JsValue::from_str("clipboard unavailable"))?;
+    let write_text: Function =
+        Reflect::get(&clipboard, &JsValue::from_str("writeText"))?.dyn_into()?;
+    let promise: Promise = write_text
+        .call1(&clipboard, &JsValue::from_str(text))?
+        .dyn_into()?;
+    JsFuture::from(promise).await.map(|_| ())
+}
@@ -0,0 +1,24 @@
+use gloo::file::Blob;
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{HtmlAnchorElement, Url};
+
+/// Saves `contents` as a file called `filename` by clicking a temporary link
+/// to an object URL.
+pub fn save_file(filename: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+    let blob: web_sys::Blob = Blob::new_with_options(contents, Some(mime_type)).into();
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let anchor: HtmlAnchorElement = gloo::utils::document()
+        .create_element("a")?
+        .dyn_into()
+        .map_err(JsValue::from)?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+    // Beri browser waktu memulai unduhan sebelum URL dilepas
+    Timeout::new(1000, move || {
+        let _ = Url::revoke_object_url(&url);
+    })
+    .forget();
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use reqwasm::http::Request;
+use serde::Deserialize;
+
+const TENOR_SEARCH_URL: &str = "https://tenor.googleapis.com/v2/search";
+// Global JS yang bisa diset halaman sebelum wasm dimuat
+const API_KEY_GLOBAL: &str = "__TENOR_API_KEY__";
+const SEARCH_LIMIT: u32 = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gif {
+    // URL .gif ukuran penuh, dikirim sebagai pesan
+    pub url: String,
+    // Versi kecil untuk grid hasil pencarian
+    pub preview_url: String,
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    content_description: String,
+    media_formats: MediaFormats,
+}
+
+#[derive(Deserialize)]
+struct MediaFormats {
+    gif: Option<MediaFormat>,
+    tinygif: Option<MediaFormat>,
+}
+
+#[derive(Deserialize)]
+struct MediaFormat {
+    url: String,
+}
+
+/// The Tenor API key, taken from `window.__TENOR_API_KEY__` at runtime or the
+/// `TENOR_API_KEY` environment variable at compile time.
+fn api_key() -> Option<String> {
+    let runtime = web_sys::window()
+        .and_then(|window| js_sys::Reflect::get(&window, &API_KEY_GLOBAL.into()).ok())
+        .and_then(|value| value.as_string())
+        .filter(|key| !key.is_empty());
+    runtime.or_else(|| option_env!("TENOR_API_KEY").map(String::from))
+}
+
+pub fn is_configured() -> bool {
+    api_key().is_some()
+}
+
+/// Searches Tenor for GIFs matching `query`.
+pub async fn search_gifs(query: &str) -> Result<Vec<Gif>, String> {
+    let key = api_key().ok_or_else(|| "GIF search isn't configured".to_string())?;
+    let url = format!(
+        "{}?q={}&key={}&limit={}&media_filter=gif,tinygif",
+        TENOR_SEARCH_URL,
+        js_sys::encode_uri_component(query),
+        js_sys::encode_uri_component(&key),
+        SEARCH_LIMIT
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't reach the GIF service ({})", e))?;
+    if !response.ok() {
+        return Err(format!("GIF search failed ({})", response.status()));
+    }
+    let body: SearchResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected response from the GIF service ({})", e))?;
+    Ok(body
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            let gif = result.media_formats.gif?;
+            let preview_url = result
+                .media_formats
+                .tinygif
+                .map_or_else(|| gif.url.clone(), |tiny| tiny.url);
+            Some(Gif {
+                url: gif.url,
+                preview_url,
+                description: result.content_description,
+            })
+        })
+        .collect())
+}
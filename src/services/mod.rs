@@ -1,2 +1,6 @@
 pub mod websocket;
 pub mod event_bus;
+pub mod notification;
+pub mod clipboard;
+pub mod download;
+pub mod gif;
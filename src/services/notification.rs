@@ -0,0 +1,46 @@
+use wasm_bindgen::JsValue;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+const MAX_BODY_CHARS: usize = 120;
+
+// `Notification` tidak ada di beberapa browser (misal Safari iOS lama)
+fn is_supported() -> bool {
+    web_sys::window()
+        .map(|window| {
+            js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_BODY_CHARS {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_BODY_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Asks for notification permission if the user hasn't decided yet. Must be
+/// called from a user interaction for browsers to show the prompt.
+pub fn request_permission() {
+    if !is_supported() || Notification::permission() != NotificationPermission::Default {
+        return;
+    }
+    if let Err(e) = Notification::request_permission() {
+        log::debug!("error requesting notification permission: {:?}", e);
+    }
+}
+
+/// Shows a desktop notification, silently doing nothing when the API is
+/// unavailable or permission wasn't granted.
+pub fn notify(title: &str, body: &str) {
+    if !is_supported() || Notification::permission() != NotificationPermission::Granted {
+        return;
+    }
+    let mut options = NotificationOptions::new();
+    options.body(&truncate(body));
+    if let Err(e) = Notification::new_with_options(title, &options) {
+        log::debug!("error showing notification: {:?}", e);
+    }
+}
@@ -1,53 +1,199 @@
-use futures::{channel::mpsc::Sender, SinkExt, StreamExt};
-use reqwasm::websocket::{futures::WebSocket, Message};
+use std::pin::Pin;
+
+use futures::{channel::mpsc::Sender, future, select, FutureExt, Sink, SinkExt, StreamExt};
+use gloo::timers::future::TimeoutFuture;
+use reqwasm::websocket::{futures::WebSocket, Message, State};
 
 use wasm_bindgen_futures::spawn_local;
+use yew::Callback;
 use yew_agent::Dispatched;
 
 use crate::services::event_bus::{EventBus, Request};
 
+const DEFAULT_WS_URL: &str = "ws://localhost:8080";
+// Global JS yang bisa diset halaman sebelum wasm dimuat
+const WS_URL_GLOBAL: &str = "__CHAT_WS_URL__";
+const INITIAL_BACKOFF_MS: u32 = 1_000;
+const MAX_BACKOFF_MS: u32 = 30_000;
+// Ping dikirim setelah koneksi diam selama ini; tanpa balasan dalam
+// PONG_TIMEOUT_MS socket dianggap mati dan disambung ulang
+const HEARTBEAT_INTERVAL_MS: u32 = 25_000;
+const PONG_TIMEOUT_MS: u32 = 10_000;
+const PING_MESSAGE: &str = r#"{"messageType":"ping"}"#;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionState {
+    Connected,
+    Connecting,
+    Disconnected,
+}
+
 pub struct WebsocketService {
     pub tx: Sender<String>,
+    reconnect: Sender<()>,
 }
 
-impl WebsocketService {
-    pub fn new() -> Self {
-        let ws = WebSocket::open("ws://localhost:8080").unwrap();
+/// Delay before reconnect attempt number `attempt` (0-based): 1s, 2s, 4s, ...
+/// capped at `MAX_BACKOFF_MS`.
+fn backoff_delay(attempt: u32) -> u32 {
+    INITIAL_BACKOFF_MS
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF_MS)
+}
 
-        let (mut write, mut read) = ws.split();
+/// Whether `data` is the server's reply to a heartbeat ping.
+fn is_pong(data: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(data)
+        .map(|value| value["messageType"] == "pong")
+        .unwrap_or(false)
+}
+
+/// The server URL, taken from the first of these that is set:
+/// 1. `window.__CHAT_WS_URL__` at runtime, so one build can target any backend;
+/// 2. the `CHAT_WS_URL` environment variable at compile time;
+/// 3. `DEFAULT_WS_URL`.
+fn configured_url() -> String {
+    let runtime = web_sys::window()
+        .and_then(|window| js_sys::Reflect::get(&window, &WS_URL_GLOBAL.into()).ok())
+        .and_then(|value| value.as_string())
+        .filter(|url| !url.is_empty());
+    runtime
+        .or_else(|| option_env!("CHAT_WS_URL").map(String::from))
+        .unwrap_or_else(|| DEFAULT_WS_URL.to_string())
+}
+
+impl WebsocketService {
+    /// Connects to the configured server URL (see `configured_url`).
+    pub fn new(max_retries: u32, on_state_change: Callback<ConnectionState>) -> Self {
+        Self::with_url(&configured_url(), max_retries, on_state_change)
+    }
 
+    /// Opens the connection to `url` and keeps it alive, reconnecting with
+    /// exponential backoff up to `max_retries` consecutive failures. Messages
+    /// sent on `tx` while disconnected are queued and delivered once reconnected.
+    /// A quiet connection is pinged every `HEARTBEAT_INTERVAL_MS` and dropped
+    /// if the pong doesn't arrive within `PONG_TIMEOUT_MS`. After giving up it
+    /// waits for [`reconnect_now`](Self::reconnect_now) before trying again.
+    pub fn with_url(
+        url: &str,
+        max_retries: u32,
+        on_state_change: Callback<ConnectionState>,
+    ) -> Self {
+        let url = url.to_string();
         let (in_tx, mut in_rx) = futures::channel::mpsc::channel::<String>(1000);
+        let (reconnect_tx, mut reconnect_rx) = futures::channel::mpsc::channel::<()>(1);
         let mut event_bus = EventBus::dispatcher();
 
         spawn_local(async move {
-            while let Some(s) = in_rx.next().await {
-                log::debug!("got event from channel! {}", s);
-                write.send(Message::Text(s)).await.unwrap();
-            }
-        });
+            let mut attempt = 0;
+            on_state_change.emit(ConnectionState::Connecting);
+            loop {
+                match WebSocket::open(&url) {
+                    Ok(mut ws) => {
+                        // Tunggu sampai socket selesai handshake (open atau gagal)
+                        let _ = future::poll_fn(|cx| Pin::new(&mut ws).poll_ready(cx)).await;
 
-        spawn_local(async move {
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(data)) => {
-                        log::debug!("from websocket: {}", data);
-                        event_bus.send(Request::EventBusMsg(data));
-                    }
-                    Ok(Message::Bytes(b)) => {
-                        let decoded = std::str::from_utf8(&b);
-                        if let Ok(val) = decoded {
-                            log::debug!("from websocket: {}", val);
-                            event_bus.send(Request::EventBusMsg(val.into()));
+                        if matches!(ws.state(), State::Open) {
+                            attempt = 0;
+                            // Permintaan reconnect yang tersisa tidak berlaku lagi
+                            while let Ok(Some(())) = reconnect_rx.try_next() {}
+                            on_state_change.emit(ConnectionState::Connected);
+                            let mut heartbeat = TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).fuse();
+                            let mut awaiting_pong = false;
+
+                            loop {
+                                select! {
+                                    msg = ws.next().fuse() => {
+                                        // Pesan apa pun membuktikan koneksi masih hidup
+                                        if matches!(msg, Some(Ok(_))) {
+                                            awaiting_pong = false;
+                                            heartbeat = TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).fuse();
+                                        }
+                                        match msg {
+                                            Some(Ok(Message::Text(data))) if is_pong(&data) => {}
+                                            Some(Ok(Message::Text(data))) => {
+                                                log::debug!("from websocket: {}", data);
+                                                event_bus.send(Request::EventBusMsg(data));
+                                            }
+                                            Some(Ok(Message::Bytes(b))) => {
+                                                let decoded = std::str::from_utf8(&b);
+                                                if let Ok(val) = decoded {
+                                                    log::debug!("from websocket: {}", val);
+                                                    event_bus.send(Request::EventBusMsg(val.into()));
+                                                }
+                                            }
+                                            Some(Err(e)) => {
+                                                log::error!("ws: {:?}", e)
+                                            }
+                                            None => break,
+                                        }
+                                    },
+                                    _ = heartbeat => {
+                                        if awaiting_pong {
+                                            log::warn!("no pong within {}ms, reconnecting", PONG_TIMEOUT_MS);
+                                            break;
+                                        }
+                                        if let Err(e) = ws.send(Message::Text(PING_MESSAGE.to_string())).await {
+                                            log::error!("ws ping: {:?}", e);
+                                        }
+                                        awaiting_pong = true;
+                                        heartbeat = TimeoutFuture::new(PONG_TIMEOUT_MS).fuse();
+                                    },
+                                    out = in_rx.next() => match out {
+                                        Some(s) => {
+                                            log::debug!("got event from channel! {}", s);
+                                            if let Err(e) = ws.send(Message::Text(s)).await {
+                                                log::error!("ws send: {:?}", e);
+                                            }
+                                        }
+                                        // Semua sender sudah di-drop, service tidak dipakai lagi
+                                        None => return,
+                                    },
+                                }
+                            }
+                            log::debug!("WebSocket Closed");
                         }
                     }
                     Err(e) => {
-                        log::error!("ws: {:?}", e)
+                        log::error!("ws open: {:?}", e);
+                    }
+                }
+
+                if attempt >= max_retries {
+                    log::debug!("giving up after {} reconnect attempts", attempt);
+                    on_state_change.emit(ConnectionState::Disconnected);
+                    // Tunggu user menekan "Reconnect now"; None berarti service sudah di-drop
+                    if reconnect_rx.next().await.is_none() {
+                        return;
                     }
+                    attempt = 0;
+                    on_state_change.emit(ConnectionState::Connecting);
+                    continue;
+                }
+
+                let delay = backoff_delay(attempt);
+                attempt += 1;
+                log::debug!("reconnecting in {}ms (attempt {})", delay, attempt);
+                on_state_change.emit(ConnectionState::Connecting);
+                select! {
+                    _ = TimeoutFuture::new(delay).fuse() => {},
+                    _ = reconnect_rx.next() => log::debug!("reconnect requested, skipping backoff"),
                 }
             }
-            log::debug!("WebSocket Closed");
         });
 
-        Self { tx: in_tx }
+        Self {
+            tx: in_tx,
+            reconnect: reconnect_tx,
+        }
+    }
+
+    /// Cuts the current backoff delay short, or starts over after giving up,
+    /// so the next connection attempt happens right away.
+    pub fn reconnect_now(&self) {
+        // Penuh berarti permintaan sebelumnya belum diproses, cukup satu
+        let _ = self.reconnect.clone().try_send(());
     }
 }
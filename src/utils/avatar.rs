@@ -0,0 +1,126 @@
+use crate::utils::emoji::{extends_cluster, is_regional_indicator};
+
+/// Avatar names offered on the login screen. Any string works as an avatar,
+/// it only seeds the generated image.
+pub const AVATARS: &[&str] = &[
+    "alex", "sam", "jordan", "riley", "casey", "morgan", "taylor", "jamie",
+];
+
+pub fn avatar_url(avatar: &str) -> String {
+    format!(
+        "https://api.dicebear.com/7.x/adventurer-neutral/svg?seed={}",
+        String::from(js_sys::encode_uri_component(avatar))
+    )
+}
+
+/// The first user-perceived character of `name` for a text avatar, "?" if
+/// `name` is empty. Keeps emoji sequences (ZWJ families, skin tones, flags)
+/// and combining accents whole, and uppercases letters only.
+pub fn avatar_initial(name: &str) -> String {
+    let mut chars = name.trim_start().chars().peekable();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return "?".to_string(),
+    };
+    let mut initial = String::from(first);
+    // Bendera adalah pasangan regional indicator
+    if is_regional_indicator(first) {
+        if let Some(second) = chars.next_if(|&c| is_regional_indicator(c)) {
+            initial.push(second);
+        }
+    }
+    loop {
+        if let Some(c) = chars.next_if(|&c| extends_cluster(c)) {
+            initial.push(c);
+        } else if let Some(zwj) = chars.next_if_eq(&'\u{200D}') {
+            // Zero-width joiner menyambung emoji berikutnya (mis. 👩‍💻)
+            initial.push(zwj);
+            match chars.next() {
+                Some(c) => initial.push(c),
+                None => break,
+            }
+        } else {
+            break;
+        }
+    }
+    if first.is_alphabetic() {
+        initial.to_uppercase()
+    } else {
+        initial
+    }
+}
+
+/// Relative luminance (WCAG 2.x) of a "#rrggbb" color, from 0 (black) to 1
+/// (white), or `None` if `hex` isn't in that form.
+pub fn relative_luminance(hex: &str) -> Option<f64> {
+    let digits = hex.strip_prefix('#').filter(|d| d.len() == 6)?;
+    let channel = |i: usize| -> Option<f64> {
+        let c = u8::from_str_radix(digits.get(i..i + 2)?, 16).ok()? as f64 / 255.0;
+        // Ubah dari sRGB ke linear
+        Some(if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        })
+    };
+    Some(0.2126 * channel(0)? + 0.7152 * channel(2)? + 0.0722 * channel(4)?)
+}
+
+/// Near-black or white, whichever has the higher contrast ratio against the
+/// background `bg` ("#rrggbb"). Unreadable colors get white.
+pub fn contrast_text_color(bg: &str) -> &'static str {
+    // Titik impas rasio kontras hitam dan putih: (L + 0.05)² = 1.05 × 0.05
+    match relative_luminance(bg) {
+        Some(luminance) if luminance > 0.179 => "#111827",
+        _ => "#FFFFFF",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_keeps_emoji_whole() {
+        assert_eq!(avatar_initial("🦀 crab"), "🦀");
+        assert_eq!(avatar_initial("👍🏽nice"), "👍🏽");
+        assert_eq!(avatar_initial("👩\u{200D}💻dev"), "👩\u{200D}💻");
+        assert_eq!(avatar_initial("🇮🇩 indo"), "🇮🇩");
+    }
+
+    #[test]
+    fn initial_uppercases_accented_letters() {
+        assert_eq!(avatar_initial("élodie"), "É");
+        // e + combining acute accent tetap satu huruf
+        assert_eq!(avatar_initial("e\u{301}mile"), "E\u{301}");
+        assert_eq!(avatar_initial("  ßen"), "SS");
+        assert_eq!(avatar_initial("42"), "4");
+    }
+
+    #[test]
+    fn initial_of_empty_name_is_question_mark() {
+        assert_eq!(avatar_initial(""), "?");
+        assert_eq!(avatar_initial("   "), "?");
+    }
+
+    #[test]
+    fn luminance_of_black_white_and_invalid() {
+        assert_eq!(relative_luminance("#000000"), Some(0.0));
+        assert!((relative_luminance("#FFFFFF").unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(relative_luminance("#fff"), None);
+        assert_eq!(relative_luminance("123456"), None);
+        assert_eq!(relative_luminance("#12345g"), None);
+    }
+
+    #[test]
+    fn text_color_flips_at_the_threshold() {
+        // #757575 sedikit di bawah ambang 0.179, #767676 sedikit di atasnya
+        assert!(relative_luminance("#757575").unwrap() < 0.179);
+        assert!(relative_luminance("#767676").unwrap() > 0.179);
+        assert_eq!(contrast_text_color("#757575"), "#FFFFFF");
+        assert_eq!(contrast_text_color("#767676"), "#111827");
+        assert_eq!(contrast_text_color("#000000"), "#FFFFFF");
+        assert_eq!(contrast_text_color("#FFFF00"), "#111827");
+        assert_eq!(contrast_text_color("not a color"), "#FFFFFF");
+    }
+}
@@ -0,0 +1,93 @@
+pub const SHRUG: &str = r"¯\_(ツ)_/¯";
+
+#[derive(Debug, PartialEq)]
+pub enum Command<'a> {
+    /// Plain text to send as-is.
+    Say(&'a str),
+    Clear,
+    Shrug(&'a str),
+    Me(&'a str),
+    Unknown(&'a str),
+}
+
+/// Parses a chat input line. Lines starting with `/` are commands; `//` escapes
+/// a literal leading slash.
+pub fn parse_command(input: &str) -> Command<'_> {
+    let input = input.trim();
+    let rest = match input.strip_prefix('/') {
+        Some(rest) => rest,
+        None => return Command::Say(input),
+    };
+    if rest.starts_with('/') {
+        return Command::Say(rest);
+    }
+    let (name, args) = rest
+        .split_once(char::is_whitespace)
+        .map_or((rest, ""), |(name, args)| (name, args.trim()));
+    match name.to_lowercase().as_str() {
+        "clear" => Command::Clear,
+        "shrug" => Command::Shrug(args),
+        "me" => Command::Me(args),
+        _ => Command::Unknown(name),
+    }
+}
+
+pub fn shrug(text: &str) -> String {
+    if text.is_empty() {
+        SHRUG.to_string()
+    } else {
+        format!("{} {}", text, SHRUG)
+    }
+}
+
+/// `/me` messages travel as their raw text so every client can render them;
+/// this returns the action part ("eats lunch" for "/me eats lunch").
+pub fn action_text(message: &str) -> Option<&str> {
+    message
+        .strip_prefix("/me ")
+        .map(str::trim)
+        .filter(|action| !action.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_me() {
+        assert_eq!(parse_command("/me eats lunch"), Command::Me("eats lunch"));
+        assert_eq!(parse_command("/ME  waves "), Command::Me("waves"));
+        assert_eq!(action_text("/me eats lunch"), Some("eats lunch"));
+        assert_eq!(action_text("/me "), None);
+    }
+
+    #[test]
+    fn parses_shrug() {
+        assert_eq!(parse_command("/shrug"), Command::Shrug(""));
+        assert_eq!(parse_command("/shrug oh well"), Command::Shrug("oh well"));
+        assert_eq!(shrug(""), SHRUG);
+        assert_eq!(shrug("oh well"), format!("oh well {}", SHRUG));
+    }
+
+    #[test]
+    fn parses_clear() {
+        assert_eq!(parse_command("/clear"), Command::Clear);
+        assert_eq!(parse_command("  /Clear  "), Command::Clear);
+    }
+
+    #[test]
+    fn unknown_commands_keep_their_name() {
+        assert_eq!(parse_command("/dance now"), Command::Unknown("dance"));
+        assert_eq!(parse_command("/"), Command::Unknown(""));
+    }
+
+    #[test]
+    fn double_slash_escapes_a_command() {
+        assert_eq!(parse_command("//clear"), Command::Say("/clear"));
+        assert_eq!(
+            parse_command("// not a command"),
+            Command::Say("/ not a command")
+        );
+        assert_eq!(parse_command("hello /me"), Command::Say("hello /me"));
+    }
+}
@@ -0,0 +1,115 @@
+// Batas jumlah emoji yang masih ditampilkan besar tanpa bubble
+pub const MAX_JUMBO_EMOJI: usize = 6;
+
+/// Whether `c` continues the grapheme cluster before it: combining marks,
+/// variation selectors, emoji skin tones, tag characters and keycaps.
+pub fn extends_cluster(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+pub fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Whether `c` can start an emoji. Approximated by the Unicode blocks emoji
+/// live in, plus the few scattered symbols like © and ‼.
+fn is_emoji_base(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}'
+        | '\u{2600}'..='\u{27BF}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{2B00}'..='\u{2BFF}'
+        | '\u{2190}'..='\u{21FF}'
+        | '\u{25A0}'..='\u{25FF}'
+        | '\u{2934}' | '\u{2935}'
+        | '\u{3030}' | '\u{303D}' | '\u{3297}' | '\u{3299}'
+        | '\u{00A9}' | '\u{00AE}' | '\u{203C}' | '\u{2049}'
+        | '\u{2122}' | '\u{2139}' | '\u{24C2}'
+    )
+}
+
+/// Whether `text` is just emoji and whitespace, with at least one and at
+/// most `MAX_JUMBO_EMOJI` emoji. Skin tones, variation selectors, ZWJ
+/// sequences, flags and keycaps (1️⃣) each count as a single emoji.
+pub fn is_emoji_only(text: &str) -> bool {
+    let mut chars = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut count = 0;
+    while let Some(first) = chars.next() {
+        let mut cluster = String::from(first);
+        // Bendera adalah pasangan regional indicator
+        if is_regional_indicator(first) {
+            if let Some(second) = chars.next_if(|&c| is_regional_indicator(c)) {
+                cluster.push(second);
+            }
+        }
+        loop {
+            if let Some(c) = chars.next_if(|&c| extends_cluster(c)) {
+                cluster.push(c);
+            } else if let Some(zwj) = chars.next_if_eq(&'\u{200D}') {
+                cluster.push(zwj);
+                match chars.next() {
+                    Some(c) => cluster.push(c),
+                    None => break,
+                }
+            } else {
+                break;
+            }
+        }
+        // Angka, # dan * hanya emoji sebagai keycap (diikuti U+20E3)
+        let emoji = if matches!(first, '0'..='9' | '#' | '*') {
+            cluster.contains('\u{20E3}')
+        } else {
+            is_emoji_base(first)
+        };
+        count += 1;
+        if !emoji || count > MAX_JUMBO_EMOJI {
+            return false;
+        }
+    }
+    count > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_text_is_not_emoji_only() {
+        assert!(!is_emoji_only("hi 👋"));
+        assert!(!is_emoji_only("👍!"));
+        assert!(!is_emoji_only("1"));
+        assert!(!is_emoji_only(""));
+        assert!(!is_emoji_only("   "));
+    }
+
+    #[test]
+    fn variation_selectors_stay_with_their_emoji() {
+        assert!(is_emoji_only("❤️"));
+        assert!(is_emoji_only("☕\u{FE0F} ⚠️"));
+        assert!(is_emoji_only("1\u{FE0F}\u{20E3}"));
+        assert!(is_emoji_only(&"❤️".repeat(MAX_JUMBO_EMOJI)));
+    }
+
+    #[test]
+    fn skin_tones_and_sequences_count_once() {
+        assert!(is_emoji_only("👍🏽"));
+        assert!(is_emoji_only(&"👍🏿".repeat(MAX_JUMBO_EMOJI)));
+        assert!(is_emoji_only("👨\u{200D}👩\u{200D}👧 🇮🇩"));
+    }
+
+    #[test]
+    fn caps_at_max_jumbo_emoji() {
+        assert!(is_emoji_only(&"😀".repeat(MAX_JUMBO_EMOJI)));
+        assert!(is_emoji_only(&"😀 ".repeat(MAX_JUMBO_EMOJI)));
+        assert!(!is_emoji_only(&"😀".repeat(MAX_JUMBO_EMOJI + 1)));
+    }
+}
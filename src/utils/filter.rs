@@ -0,0 +1,62 @@
+// Daftar bawaan untuk filter kata; dicocokkan per kata utuh tanpa peduli huruf besar
+pub const FILTERED_WORDS: [&str; 7] = ["ass", "bastard", "bitch", "crap", "damn", "fuck", "shit"];
+
+/// Masks every whole word of `text` that matches one of `words`
+/// (case-insensitively) with asterisks of the same length. Words are runs of
+/// letters and digits, so "ass" doesn't match inside "class".
+pub fn filter_text(text: &str, words: &[&str]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word_start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_alphanumeric() && i < text.len(), word_start) {
+            (true, None) => word_start = Some(i),
+            (false, Some(start)) => {
+                let word = &text[start..i];
+                let lower = word.to_lowercase();
+                if words.iter().any(|w| w.to_lowercase() == lower) {
+                    out.extend(std::iter::repeat_n('*', word.chars().count()));
+                } else {
+                    out.push_str(word);
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+        if word_start.is_none() && i < text.len() {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_whole_words_are_masked() {
+        assert_eq!(filter_text("first class", &FILTERED_WORDS), "first class");
+        assert_eq!(
+            filter_text("assess the crap", &FILTERED_WORDS),
+            "assess the ****"
+        );
+        assert_eq!(filter_text("ass-kicking", &FILTERED_WORDS), "***-kicking");
+        assert_eq!(filter_text("damn2", &FILTERED_WORDS), "damn2");
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert_eq!(
+            filter_text("DAMN it, Damn", &FILTERED_WORDS),
+            "**** it, ****"
+        );
+        assert_eq!(filter_text("heck", &["HECK"]), "****");
+    }
+
+    #[test]
+    fn mask_keeps_length_and_surroundings() {
+        assert_eq!(filter_text("  shit!\nok", &FILTERED_WORDS), "  ****!\nok");
+        assert_eq!(filter_text("ÄRGER", &["ärger"]), "*****");
+        assert_eq!(filter_text("", &FILTERED_WORDS), "");
+    }
+}
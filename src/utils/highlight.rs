@@ -0,0 +1,64 @@
+use yew::prelude::*;
+
+/// Byte length of the prefix of `haystack` that matches `needle` ignoring
+/// case, if there is one.
+fn match_len(haystack: &str, needle: &str) -> Option<usize> {
+    let mut needle = needle.chars().flat_map(char::to_lowercase).peekable();
+    let mut len = 0;
+    for (i, c) in haystack.char_indices() {
+        if needle.peek().is_none() {
+            break;
+        }
+        for lower in c.to_lowercase() {
+            if needle.next() != Some(lower) {
+                return None;
+            }
+        }
+        len = i + c.len_utf8();
+    }
+    needle.peek().is_none().then_some(len)
+}
+
+/// Byte ranges of the non-overlapping, case-insensitive matches of `query`.
+pub fn find_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    if query.is_empty() {
+        return matches;
+    }
+    let mut i = 0;
+    while i < text.len() {
+        match match_len(&text[i..], query) {
+            Some(len) if len > 0 => {
+                matches.push((i, i + len));
+                i += len;
+            }
+            _ => i += text[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    matches
+}
+
+/// Renders `text` with every case-insensitive match of `query` wrapped in
+/// `<mark>`.
+pub fn highlight(text: &str, query: &str) -> Html {
+    let mut nodes = Vec::new();
+    let mut last = 0;
+    for (start, end) in find_matches(text, query) {
+        if last < start {
+            nodes.push(html! { {&text[last..start]} });
+        }
+        nodes.push(
+            html! { <mark class="rounded bg-yellow-300 text-gray-900">{&text[start..end]}</mark> },
+        );
+        last = end;
+    }
+    if last < text.len() {
+        nodes.push(html! { {&text[last..]} });
+    }
+    nodes.into_iter().collect::<Html>()
+}
+
+/// Case-insensitive substring check matching the rules of [`highlight`].
+pub fn contains_ignore_case(text: &str, query: &str) -> bool {
+    query.is_empty() || !find_matches(text, query).is_empty()
+}
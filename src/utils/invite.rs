@@ -0,0 +1,23 @@
+//! Shareable links that open the chat in a given room. The room travels as a
+//! `?room=` query parameter: the login page passes it on to `/chat`, which
+//! joins that room instead of the default one.
+
+use web_sys::Url;
+
+pub const ROOM_PARAM: &str = "room";
+
+/// Link to the login page of this site that joins `room` after signing in.
+pub fn invite_link(room: &str) -> Option<String> {
+    let origin = gloo::utils::window().location().origin().ok()?;
+    let url = Url::new(&origin).ok()?;
+    url.search_params().set(ROOM_PARAM, room);
+    Some(url.href())
+}
+
+/// The room named by the `?room=` parameter of the current page, if any.
+pub fn room_from_location() -> Option<String> {
+    let href = gloo::utils::window().location().href().ok()?;
+    let room = Url::new(&href).ok()?.search_params().get(ROOM_PARAM)?;
+    let room = room.trim();
+    (!room.is_empty()).then(|| room.to_string())
+}
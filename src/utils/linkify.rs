@@ -0,0 +1,186 @@
+use yew::prelude::*;
+
+use crate::utils::highlight::highlight;
+use crate::utils::mention::render_mentions;
+
+// Tanda baca di akhir URL yang biasanya bagian dari kalimat, bukan link
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', '!', '?', ';', ':', ')', ']', '}', '"', '\''];
+
+#[derive(Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Link(&'a str),
+}
+
+fn is_url(word: &str) -> bool {
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| word.len() > scheme.len() && word.starts_with(scheme))
+}
+
+// SVG sengaja tidak termasuk karena bisa memuat script
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+pub const IMAGE_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Whether `s` is a base64 `data:` URL of one of the `IMAGE_MIME_TYPES`, as
+/// produced when an image file is dropped onto the chat.
+pub fn is_image_data_url(s: &str) -> bool {
+    s.strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .is_some_and(|(mime, data)| {
+            IMAGE_MIME_TYPES.contains(&mime)
+                && !data.is_empty()
+                && !data.contains(char::is_whitespace)
+        })
+}
+
+/// Whether `s` is a single `http(s)` URL pointing at a raster image, judged
+/// by the extension of its path (query and fragment ignored).
+pub fn is_image_url(s: &str) -> bool {
+    if !is_url(s) || s.contains(char::is_whitespace) {
+        return false;
+    }
+    let rest = s.split_once("://").map_or("", |(_, rest)| rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if host.is_empty() {
+        return false;
+    }
+    let path = path.split(['?', '#']).next().unwrap_or("");
+    path.rsplit_once('.').is_some_and(|(_, ext)| {
+        IMAGE_EXTENSIONS
+            .iter()
+            .any(|image| image.eq_ignore_ascii_case(ext))
+    })
+}
+
+/// The last path segment of `url` (e.g. "cat.png"), ignoring any query or
+/// fragment; the whole URL if it has no path.
+pub fn url_filename(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = rest.split(['?', '#']).next().unwrap_or(rest);
+    match path.split_once('/') {
+        Some((_, path)) => path
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .unwrap_or(url),
+        None => url,
+    }
+}
+
+/// Splits `text` into plain-text and URL segments. Whitespace is kept in the
+/// text segments so line breaks survive, and trailing punctuation such as the
+/// comma in "see https://x.com, thanks" stays out of the link.
+pub fn split_links(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+
+    for token in text.split_inclusive(char::is_whitespace) {
+        let word = token
+            .trim_end_matches(char::is_whitespace)
+            .trim_end_matches(TRAILING_PUNCTUATION);
+        if is_url(word) {
+            if text_start < pos {
+                segments.push(Segment::Text(&text[text_start..pos]));
+            }
+            segments.push(Segment::Link(word));
+            text_start = pos + word.len();
+        }
+        pos += token.len();
+    }
+    if text_start < text.len() {
+        segments.push(Segment::Text(&text[text_start..]));
+    }
+    segments
+}
+
+/// The first URL in `text` that isn't an image (images are shown inline
+/// instead), used for the link preview card.
+pub fn first_link(text: &str) -> Option<&str> {
+    split_links(text)
+        .into_iter()
+        .find_map(|segment| match segment {
+            Segment::Link(url) if !is_image_url(url) => Some(url),
+            _ => None,
+        })
+}
+
+/// Renders `text` with `http(s)://` URLs turned into links opening in a new tab
+/// and `@name` mentions as pills, marking matches of `highlight_query` (if
+/// non-empty).
+pub fn linkify(text: &str, highlight_query: &str) -> Html {
+    split_links(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => render_mentions(text, highlight_query),
+            Segment::Link(url) => html! {
+                <a href={url.to_string()} target="_blank" rel="noopener noreferrer" class="underline break-all hover:opacity-80">
+                    {highlight(url, highlight_query)}
+                </a>
+            },
+        })
+        .collect::<Html>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_text_and_links() {
+        assert_eq!(
+            split_links("go to https://example.com/a?b=1 now"),
+            vec![
+                Segment::Text("go to "),
+                Segment::Link("https://example.com/a?b=1"),
+                Segment::Text(" now"),
+            ]
+        );
+        assert_eq!(
+            split_links("http://a.io\nhttps://b.io"),
+            vec![
+                Segment::Link("http://a.io"),
+                Segment::Text("\n"),
+                Segment::Link("https://b.io"),
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_text_is_one_segment() {
+        assert_eq!(
+            split_links("no links here"),
+            vec![Segment::Text("no links here")]
+        );
+        assert_eq!(split_links("https://"), vec![Segment::Text("https://")]);
+        assert!(split_links("").is_empty());
+    }
+
+    #[test]
+    fn trailing_punctuation_stays_out_of_the_link() {
+        assert_eq!(
+            split_links("see https://x.com, thanks"),
+            vec![
+                Segment::Text("see "),
+                Segment::Link("https://x.com"),
+                Segment::Text(", thanks"),
+            ]
+        );
+        assert_eq!(
+            split_links("read https://x.com/page."),
+            vec![
+                Segment::Text("read "),
+                Segment::Link("https://x.com/page"),
+                Segment::Text(".")
+            ]
+        );
+        assert_eq!(
+            split_links("done: https://x.com!"),
+            vec![
+                Segment::Text("done: "),
+                Segment::Link("https://x.com"),
+                Segment::Text("!"),
+            ]
+        );
+    }
+}
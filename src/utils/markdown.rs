@@ -0,0 +1,295 @@
+use yew::prelude::*;
+
+use crate::utils::highlight::highlight;
+use crate::utils::linkify::linkify;
+
+#[derive(Debug, PartialEq)]
+pub enum Inline<'a> {
+    Text(&'a str),
+    Bold(Vec<Inline<'a>>),
+    Italic(Vec<Inline<'a>>),
+    Code(&'a str),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Block<'a> {
+    Paragraph(&'a str),
+    // Bahasa setelah fence pembuka hanya disimpan, belum dipakai untuk styling
+    CodeBlock {
+        lang: Option<&'a str>,
+        code: &'a str,
+    },
+}
+
+const FENCE: &str = "```";
+
+/// Finds the closing `marker` for an emphasis span opened just before `rest`.
+/// Like CommonMark, the content must be non-empty and must not start or end
+/// with whitespace, so "2 * 3 * 4" stays plain text.
+fn find_emphasis_close(rest: &str, marker: &str) -> Option<usize> {
+    if rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = rest.find(marker)?;
+    let inner = &rest[..end];
+    if inner.is_empty() || inner.ends_with(char::is_whitespace) {
+        return None;
+    }
+    Some(end)
+}
+
+/// Parses `**bold**`, `*italic*` and `` `code` `` spans. Anything that isn't a
+/// well-formed span, including unmatched markers, is kept as literal text.
+pub fn parse(text: &str) -> Vec<Inline<'_>> {
+    let mut nodes = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        // (panjang marker pembuka, panjang isi, node)
+        let span = if let Some(code) = rest.strip_prefix('`') {
+            code.find('`')
+                .filter(|&end| end > 0)
+                .map(|end| (1, end, Inline::Code(&code[..end])))
+        } else if let Some(bold) = rest.strip_prefix("**") {
+            find_emphasis_close(bold, "**").map(|end| (2, end, Inline::Bold(parse(&bold[..end]))))
+        } else if let Some(italic) = rest.strip_prefix('*') {
+            find_emphasis_close(italic, "*")
+                .map(|end| (1, end, Inline::Italic(parse(&italic[..end]))))
+        } else {
+            None
+        };
+
+        match span {
+            Some((marker_len, inner_len, node)) => {
+                if text_start < i {
+                    nodes.push(Inline::Text(&text[text_start..i]));
+                }
+                nodes.push(node);
+                i += 2 * marker_len + inner_len;
+                text_start = i;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if text_start < text.len() {
+        nodes.push(Inline::Text(&text[text_start..]));
+    }
+    nodes
+}
+
+/// Splits `text` into paragraphs and ```` ``` ```` fenced code blocks. A word
+/// right after the opening fence (on its own line) is taken as the language
+/// hint, and the line breaks just inside the fences and right around a block
+/// are dropped. An unclosed fence is kept as literal text.
+pub fn split_code_blocks(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find(FENCE) {
+        let after_open = &rest[open + FENCE.len()..];
+        let close = match after_open.find(FENCE) {
+            Some(close) => close,
+            None => break,
+        };
+        let mut inner = &after_open[..close];
+        let mut lang = None;
+        if let Some((first_line, body)) = inner.split_once('\n') {
+            let hint = first_line.trim();
+            if !hint.contains(char::is_whitespace) {
+                lang = (!hint.is_empty()).then_some(hint);
+                inner = body;
+            }
+        }
+        let paragraph = rest[..open].strip_suffix('\n').unwrap_or(&rest[..open]);
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph));
+        }
+        blocks.push(Block::CodeBlock {
+            lang,
+            code: inner.strip_suffix('\n').unwrap_or(inner),
+        });
+        let after_close = &after_open[close + FENCE.len()..];
+        rest = after_close.strip_prefix('\n').unwrap_or(after_close);
+    }
+    if !rest.is_empty() {
+        blocks.push(Block::Paragraph(rest));
+    }
+    blocks
+}
+
+fn render_nodes(nodes: &[Inline], highlight_query: &str) -> Html {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Inline::Text(text) => linkify(text, highlight_query),
+            Inline::Bold(children) => {
+                html! { <strong>{render_nodes(children, highlight_query)}</strong> }
+            }
+            Inline::Italic(children) => {
+                html! { <em>{render_nodes(children, highlight_query)}</em> }
+            }
+            Inline::Code(code) => html! {
+                <code class="px-1 rounded font-mono text-xs bg-black bg-opacity-10">
+                    {highlight(code, highlight_query)}
+                </code>
+            },
+        })
+        .collect::<Html>()
+}
+
+/// Renders a message with inline Markdown; plain text is escaped by Yew, URLs
+/// in it are linkified and matches of `highlight_query` are marked.
+pub fn render_markdown(text: &str, highlight_query: &str) -> Html {
+    render_nodes(&parse(text), highlight_query)
+}
+
+/// Like [`render_markdown`], but also renders fenced code blocks with a copy
+/// button that passes the code to `on_copy`. `copied` is the text copied most
+/// recently, whose button reads "Copied!".
+pub fn render_message_body(
+    text: &str,
+    highlight_query: &str,
+    on_copy: &Callback<String>,
+    copied: Option<&str>,
+) -> Html {
+    split_code_blocks(text)
+        .into_iter()
+        .map(|block| match block {
+            Block::Paragraph(text) => render_markdown(text, highlight_query),
+            Block::CodeBlock { code, .. } => {
+                let onclick = {
+                    let (on_copy, code) = (on_copy.clone(), code.to_string());
+                    Callback::from(move |_: MouseEvent| on_copy.emit(code.clone()))
+                };
+                html! {
+                    <div class="relative my-1 group/code">
+                        <pre class="p-3 overflow-x-auto font-mono text-xs whitespace-pre rounded-lg bg-black bg-opacity-10">
+                            <code>{highlight(code, highlight_query)}</code>
+                        </pre>
+                        <button
+                            type="button"
+                            {onclick}
+                            class="absolute top-1 right-1 px-2 py-0.5 text-xs rounded bg-black bg-opacity-20 opacity-0 group-hover/code:opacity-100 focus:opacity-100 transition-opacity select-none"
+                            title="Copy code"
+                        >
+                            {if copied == Some(code) { "Copied!" } else { "Copy" }}
+                        </button>
+                    </div>
+                }
+            }
+        })
+        .collect::<Html>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bold_italic_and_code() {
+        assert_eq!(
+            parse("a **b** *c* `d`"),
+            vec![
+                Inline::Text("a "),
+                Inline::Bold(vec![Inline::Text("b")]),
+                Inline::Text(" "),
+                Inline::Italic(vec![Inline::Text("c")]),
+                Inline::Text(" "),
+                Inline::Code("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_markers_stay_literal() {
+        assert_eq!(parse("**open"), vec![Inline::Text("**open")]);
+        assert_eq!(parse("a * b"), vec![Inline::Text("a * b")]);
+        assert_eq!(parse("2 * 3 * 4"), vec![Inline::Text("2 * 3 * 4")]);
+        assert_eq!(parse("``"), vec![Inline::Text("``")]);
+        assert_eq!(parse("`tick"), vec![Inline::Text("`tick")]);
+    }
+
+    #[test]
+    fn parses_nested_spans() {
+        assert_eq!(
+            parse("**bold `code`**"),
+            vec![Inline::Bold(vec![
+                Inline::Text("bold "),
+                Inline::Code("code"),
+            ])]
+        );
+        assert_eq!(
+            parse("**a *b* c**"),
+            vec![Inline::Bold(vec![
+                Inline::Text("a "),
+                Inline::Italic(vec![Inline::Text("b")]),
+                Inline::Text(" c"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn code_is_not_parsed_further() {
+        assert_eq!(parse("`**x**`"), vec![Inline::Code("**x**")]);
+    }
+
+    #[test]
+    fn splits_multi_line_code_block() {
+        assert_eq!(
+            split_code_blocks("```\nfn main() {\n    run();\n}\n```"),
+            vec![Block::CodeBlock {
+                lang: None,
+                code: "fn main() {\n    run();\n}",
+            }]
+        );
+    }
+
+    #[test]
+    fn reads_language_hint() {
+        assert_eq!(
+            split_code_blocks("```rust\nlet x = 1;\n```"),
+            vec![Block::CodeBlock {
+                lang: Some("rust"),
+                code: "let x = 1;",
+            }]
+        );
+        // Baris pertama berisi spasi adalah kode, bukan nama bahasa
+        assert_eq!(
+            split_code_blocks("```let x = 1;\ny\n```"),
+            vec![Block::CodeBlock {
+                lang: None,
+                code: "let x = 1;\ny",
+            }]
+        );
+    }
+
+    #[test]
+    fn unclosed_fence_is_text() {
+        assert_eq!(
+            split_code_blocks("look ```\ncode"),
+            vec![Block::Paragraph("look ```\ncode")]
+        );
+    }
+
+    #[test]
+    fn keeps_text_between_code_blocks() {
+        assert_eq!(
+            split_code_blocks("before\n```\na\n```\nmiddle\n```sh\nb\n```\nafter"),
+            vec![
+                Block::Paragraph("before"),
+                Block::CodeBlock {
+                    lang: None,
+                    code: "a",
+                },
+                Block::Paragraph("middle"),
+                Block::CodeBlock {
+                    lang: Some("sh"),
+                    code: "b",
+                },
+                Block::Paragraph("after"),
+            ]
+        );
+    }
+}
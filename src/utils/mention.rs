@@ -0,0 +1,136 @@
+use yew::prelude::*;
+
+use crate::utils::highlight::highlight;
+
+#[derive(Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    // Nama tanpa '@'
+    Mention(&'a str),
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Splits `text` into plain text and `@name` mentions. An `@` only starts a
+/// mention at the beginning of the text or after a non-name character, so
+/// e-mail addresses aren't picked up, and trailing `.`/`-` is left out of the
+/// name ("thanks @alice." mentions "alice").
+pub fn split_mentions(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '@' && !prev.is_some_and(is_name_char) {
+            let name_start = i + 1;
+            let name_end = text[name_start..]
+                .find(|c: char| !is_name_char(c))
+                .map_or(text.len(), |end| name_start + end);
+            let name = text[name_start..name_end].trim_end_matches(['.', '-']);
+            if !name.is_empty() {
+                if text_start < i {
+                    segments.push(Segment::Text(&text[text_start..i]));
+                }
+                segments.push(Segment::Mention(name));
+                text_start = name_start + name.len();
+                while chars.peek().is_some_and(|&(j, _)| j < text_start) {
+                    chars.next();
+                }
+                prev = name.chars().last();
+                continue;
+            }
+        }
+        prev = Some(c);
+    }
+    if text_start < text.len() {
+        segments.push(Segment::Text(&text[text_start..]));
+    }
+    segments
+}
+
+/// Names mentioned in `text`, in order of first appearance and without
+/// duplicates.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in split_mentions(text) {
+        if let Segment::Mention(name) = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// The mention being typed at the end of `before_caret`, if any: the byte
+/// offset of its `@` and the name prefix typed so far (possibly empty).
+pub fn active_mention(before_caret: &str) -> Option<(usize, &str)> {
+    let at = before_caret.rfind('@')?;
+    let prefix = &before_caret[at + 1..];
+    let starts_mention = !before_caret[..at]
+        .chars()
+        .next_back()
+        .is_some_and(is_name_char);
+    (starts_mention && prefix.chars().all(is_name_char)).then_some((at, prefix))
+}
+
+pub fn mentions_user(text: &str, username: &str) -> bool {
+    extract_mentions(text)
+        .iter()
+        .any(|name| name.to_lowercase() == username.to_lowercase())
+}
+
+/// Renders `text` with mentions as pills and matches of `highlight_query`
+/// marked.
+pub fn render_mentions(text: &str, highlight_query: &str) -> Html {
+    split_mentions(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => highlight(text, highlight_query),
+            Segment::Mention(name) => html! {
+                <span class="px-1 rounded-full font-medium bg-yellow-200 text-yellow-900">
+                    {"@"}{highlight(name, highlight_query)}
+                </span>
+            },
+        })
+        .collect::<Html>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_punctuation_is_not_part_of_the_name() {
+        assert_eq!(extract_mentions("thanks @alice."), vec!["alice"]);
+        assert_eq!(
+            extract_mentions("@bob, @carol! @dave?"),
+            vec!["bob", "carol", "dave"]
+        );
+        assert_eq!(extract_mentions("hi @eve-"), vec!["eve"]);
+    }
+
+    #[test]
+    fn collects_multiple_mentions_once() {
+        assert_eq!(
+            extract_mentions("@alice and @bob, then @alice again"),
+            vec!["alice", "bob"]
+        );
+        assert_eq!(extract_mentions("@a.b_c-d"), vec!["a.b_c-d"]);
+    }
+
+    #[test]
+    fn email_addresses_are_not_mentions() {
+        assert!(extract_mentions("mail me at alice@example.com").is_empty());
+        assert_eq!(extract_mentions("alice@example.com cc @bob"), vec!["bob"]);
+    }
+
+    #[test]
+    fn bare_at_sign_is_not_a_mention() {
+        assert!(extract_mentions("meet @ noon").is_empty());
+        assert!(extract_mentions("@").is_empty());
+    }
+}
@@ -0,0 +1,14 @@
+pub mod avatar;
+pub mod command;
+pub mod emoji;
+pub mod filter;
+pub mod highlight;
+pub mod invite;
+pub mod linkify;
+pub mod markdown;
+pub mod mention;
+pub mod rate_limit;
+pub mod reaction;
+pub mod shortcode;
+pub mod shortcuts;
+pub mod storage;
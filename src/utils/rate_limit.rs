@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+/// Sliding-window limiter allowing at most `max` events in any `window_ms`
+/// span of time.
+pub struct RateLimiter {
+    max: usize,
+    window_ms: f64,
+    // Waktu (Unix millis) event yang masih berada di dalam jendela
+    events: VecDeque<f64>,
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, window_ms: f64) -> Self {
+        Self {
+            max,
+            window_ms,
+            events: VecDeque::with_capacity(max),
+        }
+    }
+
+    /// Records an event at `now` if the limit allows it. Otherwise returns how
+    /// many milliseconds remain until the next event would be allowed.
+    pub fn try_acquire(&mut self, now: f64) -> Result<(), f64> {
+        while self
+            .events
+            .front()
+            .is_some_and(|&t| now - t >= self.window_ms)
+        {
+            self.events.pop_front();
+        }
+        match self.events.front() {
+            Some(&oldest) if self.events.len() >= self.max => Err(oldest + self.window_ms - now),
+            _ => {
+                self.events.push_back(now);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_up_to_max_in_a_window() {
+        let mut limiter = RateLimiter::new(2, 1000.0);
+        assert_eq!(limiter.try_acquire(0.0), Ok(()));
+        assert_eq!(limiter.try_acquire(100.0), Ok(()));
+        assert_eq!(limiter.try_acquire(200.0), Err(800.0));
+    }
+
+    #[test]
+    fn events_expire_after_the_window() {
+        let mut limiter = RateLimiter::new(2, 1000.0);
+        limiter.try_acquire(0.0).unwrap();
+        limiter.try_acquire(400.0).unwrap();
+        assert_eq!(limiter.try_acquire(999.0), Err(1.0));
+        // Event pertama keluar dari jendela tepat setelah 1000ms
+        assert_eq!(limiter.try_acquire(1000.0), Ok(()));
+        assert_eq!(limiter.try_acquire(1200.0), Err(200.0));
+        assert_eq!(limiter.try_acquire(5000.0), Ok(()));
+        assert_eq!(limiter.try_acquire(5000.0), Ok(()));
+    }
+
+    #[test]
+    fn rejected_attempts_are_not_recorded() {
+        let mut limiter = RateLimiter::new(1, 1000.0);
+        limiter.try_acquire(0.0).unwrap();
+        assert_eq!(limiter.try_acquire(500.0), Err(500.0));
+        assert_eq!(limiter.try_acquire(900.0), Err(100.0));
+        assert_eq!(limiter.try_acquire(1000.0), Ok(()));
+    }
+}
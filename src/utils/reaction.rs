@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+/// Emoji offered in the hover reaction bar, also the order chips are shown in.
+pub const QUICK_REACTIONS: &[&str] = &["👍", "❤️", "😂"];
+
+/// Users who reacted to a message, keyed by emoji.
+pub type Reactions = HashMap<String, Vec<String>>;
+
+pub fn has_reacted(reactions: &Reactions, emoji: &str, user: &str) -> bool {
+    reactions
+        .get(emoji)
+        .is_some_and(|users| users.iter().any(|u| u == user))
+}
+
+/// Records `user`'s reaction. Returns false if it was already there, so
+/// echoes of our own optimistic reactions are no-ops.
+pub fn add_reaction(reactions: &mut Reactions, emoji: &str, user: &str) -> bool {
+    if has_reacted(reactions, emoji, user) {
+        return false;
+    }
+    reactions
+        .entry(emoji.to_string())
+        .or_default()
+        .push(user.to_string());
+    true
+}
+
+/// Removes `user`'s reaction, dropping the emoji once nobody is left on it.
+pub fn remove_reaction(reactions: &mut Reactions, emoji: &str, user: &str) -> bool {
+    let users = match reactions.get_mut(emoji) {
+        Some(users) => users,
+        None => return false,
+    };
+    let before = users.len();
+    users.retain(|u| u != user);
+    let removed = users.len() != before;
+    if users.is_empty() {
+        reactions.remove(emoji);
+    }
+    removed
+}
+
+/// Reactions in display order: the quick reactions first, then the rest
+/// alphabetically, so chips don't jump around between renders.
+pub fn sorted(reactions: &Reactions) -> Vec<(&str, &[String])> {
+    let mut chips: Vec<(&str, &[String])> = reactions
+        .iter()
+        .filter(|(_, users)| !users.is_empty())
+        .map(|(emoji, users)| (emoji.as_str(), users.as_slice()))
+        .collect();
+    chips.sort_by_key(|(emoji, _)| {
+        let rank = QUICK_REACTIONS
+            .iter()
+            .position(|q| q == emoji)
+            .unwrap_or(QUICK_REACTIONS.len());
+        (rank, *emoji)
+    });
+    chips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_remove_toggle_a_reaction() {
+        let mut reactions = Reactions::new();
+        assert!(add_reaction(&mut reactions, "👍", "alice"));
+        assert!(!add_reaction(&mut reactions, "👍", "alice"));
+        assert!(add_reaction(&mut reactions, "👍", "bob"));
+        assert_eq!(reactions["👍"], vec!["alice", "bob"]);
+        assert!(has_reacted(&reactions, "👍", "alice"));
+
+        assert!(remove_reaction(&mut reactions, "👍", "alice"));
+        assert!(!remove_reaction(&mut reactions, "👍", "alice"));
+        assert!(!has_reacted(&reactions, "👍", "alice"));
+        assert!(remove_reaction(&mut reactions, "👍", "bob"));
+        // Emoji tanpa user dibuang
+        assert!(reactions.is_empty());
+        assert!(!remove_reaction(&mut reactions, "❤️", "bob"));
+    }
+
+    #[test]
+    fn sorted_puts_quick_reactions_first() {
+        let mut reactions = Reactions::new();
+        for (emoji, user) in [
+            ("🎉", "a"),
+            ("😂", "b"),
+            ("🔥", "c"),
+            ("👍", "d"),
+            ("❤️", "e"),
+        ] {
+            add_reaction(&mut reactions, emoji, user);
+        }
+        reactions.insert("🙈".to_string(), Vec::new());
+        let order: Vec<&str> = sorted(&reactions)
+            .into_iter()
+            .map(|(emoji, _)| emoji)
+            .collect();
+        // Emoji tanpa user tidak ditampilkan, sisanya urut abjad (codepoint)
+        assert_eq!(order, ["👍", "❤️", "😂", "🎉", "🔥"]);
+    }
+}
@@ -0,0 +1,99 @@
+// Shortcode bawaan, mengikuti nama yang umum di Slack/GitHub
+const SHORTCODES: &[(&str, &str)] = &[
+    ("smile", "😄"),
+    ("grin", "😀"),
+    ("joy", "😂"),
+    ("wink", "😉"),
+    ("blush", "😊"),
+    ("heart_eyes", "😍"),
+    ("thinking", "🤔"),
+    ("cry", "😢"),
+    ("sob", "😭"),
+    ("angry", "😠"),
+    ("sunglasses", "😎"),
+    ("scream", "😱"),
+    ("heart", "❤️"),
+    ("broken_heart", "💔"),
+    ("thumbsup", "👍"),
+    ("+1", "👍"),
+    ("thumbsdown", "👎"),
+    ("-1", "👎"),
+    ("clap", "👏"),
+    ("wave", "👋"),
+    ("pray", "🙏"),
+    ("ok_hand", "👌"),
+    ("muscle", "💪"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("star", "⭐"),
+    ("sparkles", "✨"),
+    ("tada", "🎉"),
+    ("rocket", "🚀"),
+    ("100", "💯"),
+    ("check", "✅"),
+    ("x", "❌"),
+    ("warning", "⚠️"),
+    ("coffee", "☕"),
+    ("pizza", "🍕"),
+];
+
+fn lookup(name: &str) -> Option<&'static str> {
+    SHORTCODES
+        .iter()
+        .find(|(code, _)| *code == name)
+        .map(|(_, emoji)| *emoji)
+}
+
+/// Replaces `:name:` shortcodes in `text` with their emoji. Unknown names are
+/// left as typed, and a closing colon can't start the next code, so
+/// ":smile::heart:" becomes "😄❤️".
+pub fn expand_shortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find(':') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let emoji = after.find(':').and_then(|close| {
+            let name = &after[..close];
+            lookup(name).map(|emoji| (emoji, close))
+        });
+        match emoji {
+            Some((emoji, close)) => {
+                out.push_str(emoji);
+                rest = &after[close + 1..];
+            }
+            // Titik dua ini bukan pembuka, tapi bisa jadi pembuka berikutnya
+            None => {
+                out.push(':');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_adjacent_shortcodes() {
+        assert_eq!(expand_shortcodes(":smile::heart:"), "😄❤️");
+        assert_eq!(expand_shortcodes(":+1: :fire::fire:"), "👍 🔥🔥");
+    }
+
+    #[test]
+    fn expands_shortcodes_inside_words() {
+        assert_eq!(expand_shortcodes("nice:thumbsup:work"), "nice👍work");
+        assert_eq!(expand_shortcodes("a:x:b"), "a❌b");
+    }
+
+    #[test]
+    fn leaves_unknown_names_and_stray_colons() {
+        assert_eq!(expand_shortcodes(":nope: :smile:"), ":nope: 😄");
+        assert_eq!(expand_shortcodes("at 10:30"), "at 10:30");
+        assert_eq!(expand_shortcodes("ratio 1:2:smile:"), "ratio 1:2😄");
+        assert_eq!(expand_shortcodes(":smile"), ":smile");
+    }
+}
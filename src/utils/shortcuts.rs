@@ -0,0 +1,38 @@
+//! Global keyboard shortcuts.
+//!
+//! | Keys     | Action                                                |
+//! |----------|-------------------------------------------------------|
+//! | `Ctrl+K` | Open and focus message search                         |
+//! | `/`      | Same as `Ctrl+K`, only when not typing in a field     |
+//! | `Ctrl+D` | Switch to the next theme (light, dark, high contrast) |
+//! | `Ctrl+L` | Clear chat (press twice to confirm)                   |
+//! | `Esc`    | Close the open popover, or else clear the message box |
+//!
+//! `Cmd` works in place of `Ctrl` on macOS.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shortcut {
+    FocusSearch,
+    CycleTheme,
+    ClearChat,
+    Escape,
+}
+
+/// Maps a keydown to a shortcut. `in_field` is whether focus is in a text
+/// input, where plain keys belong to the user's typing and only modifier
+/// combos (plus `Esc`) are taken.
+pub fn shortcut_for(key: &str, ctrl: bool, in_field: bool) -> Option<Shortcut> {
+    if ctrl {
+        return match key.to_ascii_lowercase().as_str() {
+            "k" => Some(Shortcut::FocusSearch),
+            "d" => Some(Shortcut::CycleTheme),
+            "l" => Some(Shortcut::ClearChat),
+            _ => None,
+        };
+    }
+    match key {
+        "Escape" => Some(Shortcut::Escape),
+        "/" if !in_field => Some(Shortcut::FocusSearch),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+//! Small wrappers around `localStorage` and `sessionStorage`. Every failure
+//! (storage disabled, quota exceeded, private mode) is treated as "nothing
+//! stored".
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+// Hanya bertahan selama tab terbuka, termasuk saat reload
+fn session_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok().flatten()
+}
+
+pub fn load_string(key: &str) -> Option<String> {
+    local_storage().and_then(|storage| storage.get_item(key).ok().flatten())
+}
+
+pub fn save_string(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        if let Err(e) = storage.set_item(key, value) {
+            log::debug!("error saving {}: {:?}", key, e);
+        }
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+// Nilai yang tidak dikenali (misal "yes") dianggap tidak ada
+pub fn load_bool(key: &str) -> Option<bool> {
+    load_string(key).and_then(|value| value.parse().ok())
+}
+
+pub fn save_bool(key: &str, value: bool) {
+    save_string(key, &value.to_string());
+}
+
+pub fn session_load_string(key: &str) -> Option<String> {
+    session_storage().and_then(|storage| storage.get_item(key).ok().flatten())
+}
+
+/// Saves to `sessionStorage`, returning false if it failed (for example
+/// because the quota is exceeded) so the caller can store less.
+pub fn session_save_string(key: &str, value: &str) -> bool {
+    match session_storage().map(|storage| storage.set_item(key, value)) {
+        Some(Ok(())) => true,
+        Some(Err(e)) => {
+            log::debug!("error saving {}: {:?}", key, e);
+            false
+        }
+        None => false,
+    }
+}
+
+pub fn session_remove(key: &str) {
+    if let Some(storage) = session_storage() {
+        let _ = storage.remove_item(key);
+    }
+}